@@ -1,5 +1,8 @@
 use crate::args::prelude::*;
+use crate::cmus_status::source::SourceConfig;
+use crate::cmus_status::{OutputMode, StatusOptions};
 use crate::error::prelude::*;
+use std::convert::TryFrom;
 
 pub mod prelude {
     pub use super::action;
@@ -7,18 +10,13 @@ pub mod prelude {
 }
 
 pub enum Action {
-    Status,
+    Status(StatusOptions),
+    I3bar(StatusOptions),
     Help,
     Version,
     DumpConfig,
 }
 
-impl Default for Action {
-    fn default() -> Self {
-        Action::Status
-    }
-}
-
 pub fn action() -> MyResult<Action> {
     let args = Args::new()?;
 
@@ -29,13 +27,14 @@ pub fn action() -> MyResult<Action> {
             let act_or_err: MyResult<Action> = match cmd {
                 CliCommand::Status => {
                     if cmd_index == 0 {
-                        if args.options.is_empty() {
-                            Ok(Action::Status)
-                        } else {
-                            Err(Error::CommandTakesNoOptions(
-                                cmd.name().to_string(),
-                            ))
-                        }
+                        status_options(cmd, &args.options).map(Action::Status)
+                    } else {
+                        Err(Error::InvalidCommandLen(args.commands.to_string()))
+                    }
+                }
+                CliCommand::I3bar => {
+                    if cmd_index == 0 {
+                        status_options(cmd, &args.options).map(Action::I3bar)
                     } else {
                         Err(Error::InvalidCommandLen(args.commands.to_string()))
                     }
@@ -54,8 +53,53 @@ pub fn action() -> MyResult<Action> {
         action_opt = args.options.iter().find_map(|opt| match opt {
             CliOption::Help => Some(Action::Help),
             CliOption::Version => Some(Action::Version),
+            CliOption::Source(_)
+            | CliOption::Input(_)
+            | CliOption::Output(_) => None,
         });
     }
 
-    Ok(action_opt.unwrap_or_else(Action::default))
+    match action_opt {
+        Some(action) => Ok(action),
+        None => status_options(&CliCommand::Status, &args.options)
+            .map(Action::Status),
+    }
+}
+
+fn status_options(
+    cmd: &CliCommand,
+    options: &CliOptions,
+) -> MyResult<StatusOptions> {
+    options.iter().try_fold(
+        StatusOptions::default(),
+        |mut status_options, opt| {
+            match opt {
+                CliOption::Source(source) => {
+                    status_options.source =
+                        Some(SourceConfig::try_from(source.as_str())?);
+                }
+                CliOption::Input(input) => {
+                    status_options.source = Some(if input == "-" {
+                        SourceConfig::Stdin
+                    } else {
+                        SourceConfig::File(input.into())
+                    });
+                }
+                // The `i3bar` command always prints i3bar blocks.
+                CliOption::Output(output)
+                    if !matches!(cmd, CliCommand::I3bar) =>
+                {
+                    status_options.output =
+                        OutputMode::try_from(output.as_str())?;
+                }
+                CliOption::Help | CliOption::Version | CliOption::Output(_) => {
+                    return Err(Error::InvalidCommandOption(
+                        cmd.name().to_string(),
+                        opt.name_double().to_string(),
+                    ));
+                }
+            }
+            Ok(status_options)
+        },
+    )
 }
@@ -1,28 +1,34 @@
 use super::names;
 use regex::Regex;
 use std::convert::TryFrom;
+use std::fmt;
 
 #[derive(Default)]
 pub struct CliCommands(pub(super) Vec<CliCommand>);
 
 impl CliCommands {
-    pub fn iter(&self) -> std::slice::Iter<CliCommand> {
+    pub fn iter(&self) -> std::slice::Iter<'_, CliCommand> {
         self.0.iter()
     }
 }
 
-impl ToString for CliCommands {
-    fn to_string(&self) -> String {
-        self.0
-            .iter()
-            .map(CliCommand::name)
-            .collect::<Vec<&str>>()
-            .join(" ")
+impl fmt::Display for CliCommands {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.0
+                .iter()
+                .map(CliCommand::name)
+                .collect::<Vec<&str>>()
+                .join(" ")
+        )
     }
 }
 
 pub enum CliCommand {
     Status,
+    I3bar,
     Help,
     DumpConfig,
 }
@@ -31,6 +37,7 @@ impl CliCommand {
     pub fn name(&self) -> &str {
         match self {
             CliCommand::Status => names::CMD_STATUS,
+            CliCommand::I3bar => names::CMD_I3BAR,
             CliCommand::Help => names::CMD_HELP,
             CliCommand::DumpConfig => names::CMD_DUMP_CONFIG,
         }
@@ -48,6 +55,7 @@ impl TryFrom<&str> for CliCommand {
         {
             match name {
                 names::CMD_STATUS => Ok(CliCommand::Status),
+                names::CMD_I3BAR => Ok(CliCommand::I3bar),
                 names::CMD_HELP => Ok(CliCommand::Help),
                 names::CMD_DUMP_CONFIG => Ok(CliCommand::DumpConfig),
                 _ => Err(()),
@@ -9,11 +9,18 @@ pub mod prelude {
 mod names {
     pub(super) const CMD_HELP: &str = "help";
     pub(super) const CMD_STATUS: &str = "status";
+    pub(super) const CMD_I3BAR: &str = "i3bar";
     pub(super) const CMD_DUMP_CONFIG: &str = "dump-config";
     pub(super) const OPT_DOUBLE_HELP: &str = "help";
     pub(super) const OPT_DOUBLE_VERSION: &str = "version";
+    pub(super) const OPT_DOUBLE_SOURCE: &str = "source";
+    pub(super) const OPT_DOUBLE_INPUT: &str = "input";
+    pub(super) const OPT_DOUBLE_OUTPUT: &str = "output";
     pub(super) const OPT_SINGLE_HELP: char = 'h';
     pub(super) const OPT_SINGLE_VERSION: char = 'v';
+    pub(super) const OPT_SINGLE_SOURCE: char = 's';
+    pub(super) const OPT_SINGLE_INPUT: char = 'i';
+    pub(super) const OPT_SINGLE_OUTPUT: char = 'o';
 }
 
 mod commands;
@@ -33,22 +40,27 @@ pub struct Args {
 
 impl Args {
     pub fn new() -> MyResult<Self> {
-        let (commands, options) = env::args().skip(1).try_fold(
-            (CliCommands::default(), CliOptions::default()),
-            |(mut commands, mut options), arg| {
-                if let Ok(opts) = CliOptions::try_from(arg.as_str()) {
-                    options.0.append(&mut opts.into());
-                    Ok((commands, options))
-                } else {
-                    if let Ok(cmd) = CliCommand::try_from(arg.as_str()) {
-                        commands.0.push(cmd);
-                        Ok((commands, options))
-                    } else {
-                        Err(Error::InvalidArgument(arg))
+        let mut commands = CliCommands::default();
+        let mut options = CliOptions::default();
+        let mut args = env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            if let Ok(opts) = CliOptions::try_from(arg.as_str()) {
+                for mut opt in Vec::from(opts) {
+                    if opt.takes_value() {
+                        let value = args.next().ok_or_else(|| {
+                            Error::OptionMissingValue(arg.clone())
+                        })?;
+                        opt.set_value(value);
                     }
+                    options.0.push(opt);
                 }
-            },
-        )?;
+            } else if let Ok(cmd) = CliCommand::try_from(arg.as_str()) {
+                commands.0.push(cmd);
+            } else {
+                return Err(Error::InvalidArgument(arg));
+            }
+        }
 
         Ok(Self { commands, options })
     }
@@ -63,7 +75,20 @@ pub fn print_help() {
         let opt = CliOption::Version;
         format!("-{}, --{}", opt.name_single(), opt.name_double())
     };
+    let opt_source = {
+        let opt = CliOption::Source(String::new());
+        format!("-{}, --{} <SOURCE>", opt.name_single(), opt.name_double())
+    };
+    let opt_input = {
+        let opt = CliOption::Input(String::new());
+        format!("-{}, --{} <FILE>", opt.name_single(), opt.name_double())
+    };
+    let opt_output = {
+        let opt = CliOption::Output(String::new());
+        format!("-{}, --{} <OUTPUT>", opt.name_single(), opt.name_double())
+    };
     let cmd_status = CliCommand::Status.name();
+    let cmd_i3bar = CliCommand::I3bar.name();
     let cmd_help = CliCommand::Help.name();
     let cmd_dump_config = CliCommand::DumpConfig.name();
 
@@ -76,12 +101,25 @@ USAGE:
 OPTIONS:
     {opt_help:<opt_width$} Print this help message and exit.
     {opt_vers:<opt_width$} Print version information and exit.
+    {opt_source:<opt_width$} Where to get cmus' status from, overwrites
+    {empty:<opt_width$} the `source` config key. One of:
+    {empty:<opt_width$}     auto, cmus-remote, socket, tcp, stdin
+    {opt_input:<opt_width$} Read output in the format of `cmus-remote -Q`
+    {empty:<opt_width$} from the given file instead of querying cmus.
+    {empty:<opt_width$} Reads from stdin, if FILE is `-`.
+    {opt_output:<opt_width$} How to print the status. One of:
+    {empty:<opt_width$}     text    the configured format (default)
+    {empty:<opt_width$}     waybar  JSON for Waybar's custom modules
 
 COMMANDS:
     {cmd_status}
         Print the current cmus playback status
         with the format configured in the config.toml file.
         This is the default command, so you may omit this argument.
+    {cmd_i3bar}
+        Keep printing the cmus playback status as blocks of the
+        i3bar protocol, for i3bar and swaybar. Configure the blocks
+        in the [i3bar] section of the config.toml file.
     {cmd_dump_config}
         Print the default config as TOML to stdout.
         To write the default config to the proper config file, run something like:
@@ -90,11 +128,16 @@ COMMANDS:
     {cmd_help}
         Print this help message and exit."#,
         description = crate::meta::DESCRIPTION,
-        name =  crate::meta::NAME,
-        opt_width = 16,
+        name = crate::meta::NAME,
+        opt_width = 22,
+        empty = "",
         opt_help = opt_help,
         opt_vers = opt_vers,
+        opt_source = opt_source,
+        opt_input = opt_input,
+        opt_output = opt_output,
         cmd_status = cmd_status,
+        cmd_i3bar = cmd_i3bar,
         cmd_help = cmd_help,
         cmd_dump_config = cmd_dump_config,
     );
@@ -6,7 +6,7 @@ use std::convert::TryFrom;
 pub struct CliOptions(pub(super) Vec<CliOption>);
 
 impl CliOptions {
-    pub fn iter(&self) -> std::slice::Iter<CliOption> {
+    pub fn iter(&self) -> std::slice::Iter<'_, CliOption> {
         self.0.iter()
     }
 
@@ -19,9 +19,9 @@ impl CliOptions {
     }
 }
 
-impl Into<Vec<CliOption>> for CliOptions {
-    fn into(self) -> Vec<CliOption> {
-        self.0
+impl From<CliOptions> for Vec<CliOption> {
+    fn from(opts: CliOptions) -> Self {
+        opts.0
     }
 }
 
@@ -48,6 +48,15 @@ impl TryFrom<&str> for CliOptions {
                         names::OPT_DOUBLE_VERSION => {
                             Ok(vec![CliOption::Version].into())
                         }
+                        names::OPT_DOUBLE_SOURCE => {
+                            Ok(vec![CliOption::Source(String::new())].into())
+                        }
+                        names::OPT_DOUBLE_INPUT => {
+                            Ok(vec![CliOption::Input(String::new())].into())
+                        }
+                        names::OPT_DOUBLE_OUTPUT => {
+                            Ok(vec![CliOption::Output(String::new())].into())
+                        }
                         _ => Err(()),
                     },
                     // SINGLE
@@ -62,6 +71,18 @@ impl TryFrom<&str> for CliOptions {
                                 opts.push(CliOption::Version);
                                 Ok(opts)
                             }
+                            names::OPT_SINGLE_SOURCE => {
+                                opts.push(CliOption::Source(String::new()));
+                                Ok(opts)
+                            }
+                            names::OPT_SINGLE_INPUT => {
+                                opts.push(CliOption::Input(String::new()));
+                                Ok(opts)
+                            }
+                            names::OPT_SINGLE_OUTPUT => {
+                                opts.push(CliOption::Output(String::new()));
+                                Ok(opts)
+                            }
                             _ => Err(()),
                         })?
                         .into()),
@@ -80,6 +101,9 @@ impl TryFrom<&str> for CliOptions {
 pub enum CliOption {
     Help,
     Version,
+    Source(String),
+    Input(String),
+    Output(String),
 }
 
 impl CliOption {
@@ -87,6 +111,9 @@ impl CliOption {
         match self {
             CliOption::Help => names::OPT_SINGLE_HELP,
             CliOption::Version => names::OPT_SINGLE_VERSION,
+            CliOption::Source(_) => names::OPT_SINGLE_SOURCE,
+            CliOption::Input(_) => names::OPT_SINGLE_INPUT,
+            CliOption::Output(_) => names::OPT_SINGLE_OUTPUT,
         }
     }
 
@@ -94,6 +121,28 @@ impl CliOption {
         match self {
             CliOption::Help => names::OPT_DOUBLE_HELP,
             CliOption::Version => names::OPT_DOUBLE_VERSION,
+            CliOption::Source(_) => names::OPT_DOUBLE_SOURCE,
+            CliOption::Input(_) => names::OPT_DOUBLE_INPUT,
+            CliOption::Output(_) => names::OPT_DOUBLE_OUTPUT,
+        }
+    }
+
+    /// Options which take a value, from the following argument.
+    pub fn takes_value(&self) -> bool {
+        match self {
+            CliOption::Help | CliOption::Version => false,
+            CliOption::Source(_)
+            | CliOption::Input(_)
+            | CliOption::Output(_) => true,
+        }
+    }
+
+    pub(super) fn set_value(&mut self, value: String) {
+        match self {
+            CliOption::Help | CliOption::Version => (),
+            CliOption::Source(source) => *source = value,
+            CliOption::Input(input) => *input = value,
+            CliOption::Output(output) => *output = value,
         }
     }
 }
@@ -0,0 +1,174 @@
+//! A client for cmus' remote protocol, the same one `cmus-remote` uses.
+//! Querying cmus directly over its socket avoids spawning
+//! a `cmus-remote` process for every status update.
+
+use crate::config::ServerConfig;
+use crate::error::prelude::*;
+use std::env;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const STATUS_COMMAND: &[u8] = b"status\n";
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Returns the path to cmus' UNIX socket, checking in order:
+///   - `$CMUS_SOCKET`
+///   - `$XDG_RUNTIME_DIR/cmus-socket`
+///   - `socket` in cmus' config directory, see `get_config_dir`
+pub fn get_socket_path() -> Option<PathBuf> {
+    let candidates = vec![
+        env_path("CMUS_SOCKET"),
+        env_path("XDG_RUNTIME_DIR").map(|dir| dir.join("cmus-socket")),
+        get_config_dir().map(|dir| dir.join("socket")),
+    ];
+    candidates.into_iter().flatten().find(|path| path.exists())
+}
+
+/// Returns cmus' config directory, the same way cmus finds it:
+///   - `$CMUS_HOME`
+///   - `$XDG_CONFIG_HOME/cmus`
+///   - `~/.config/cmus`
+pub fn get_config_dir() -> Option<PathBuf> {
+    env_path("CMUS_HOME")
+        .or_else(|| env_path("XDG_CONFIG_HOME").map(|dir| dir.join("cmus")))
+        .or_else(|| {
+            dirs::home_dir().map(|home| home.join(".config").join("cmus"))
+        })
+}
+
+/// Returns the environment variable as a path, if it's set and not empty,
+/// like cmus treats it.
+fn env_path(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Query the `status` from cmus' UNIX socket at the default location.
+pub fn get_socket_output() -> MyResult<Vec<u8>> {
+    let path = get_socket_path().ok_or(Error::CmusSocketNotFound)?;
+    get_socket_output_from(&path)
+}
+
+/// Query the `status` from cmus' UNIX socket at the given path.
+#[cfg(unix)]
+pub fn get_socket_output_from(path: &Path) -> MyResult<Vec<u8>> {
+    use std::os::unix::net::UnixStream;
+
+    let socket_error =
+        |e: std::io::Error| Error::CmusSocketError(path.into(), e.to_string());
+    let stream = UnixStream::connect(path).map_err(socket_error)?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .map_err(socket_error)?;
+    stream
+        .set_write_timeout(Some(TIMEOUT))
+        .map_err(socket_error)?;
+    query_status(stream).map_err(socket_error)
+}
+
+#[cfg(not(unix))]
+pub fn get_socket_output_from(path: &Path) -> MyResult<Vec<u8>> {
+    Err(Error::CmusSocketError(
+        path.into(),
+        "UNIX sockets are not supported on this platform".to_string(),
+    ))
+}
+
+/// Query the `status` from a cmus instance listening on TCP/IP,
+/// started with `cmus --listen host:port`.
+pub fn get_tcp_output(server: &ServerConfig) -> MyResult<Vec<u8>> {
+    let address = format!("{}:{}", server.host, server.port);
+    let connection_error = |e: std::io::Error| {
+        Error::CmusConnectionFailed(address.clone(), e.to_string())
+    };
+
+    let mut stream = connect_tcp(server).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::TimedOut {
+            Error::CmusConnectionTimeout(address.clone())
+        } else {
+            connection_error(e)
+        }
+    })?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .map_err(connection_error)?;
+    stream
+        .set_write_timeout(Some(TIMEOUT))
+        .map_err(connection_error)?;
+
+    if authenticate(&mut stream, server.password.as_str())
+        .map_err(connection_error)?
+    {
+        query_status(stream).map_err(connection_error)
+    } else {
+        Err(Error::CmusAuthenticationFailed(address.clone()))
+    }
+}
+
+/// Try connecting to all addresses the host resolves to.
+fn connect_tcp(server: &ServerConfig) -> std::io::Result<TcpStream> {
+    let timeout = Duration::from_secs_f32(server.connect_timeout);
+    let mut last_error = std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "host didn't resolve to any address",
+    );
+    for socket_address in
+        (server.host.as_str(), server.port).to_socket_addrs()?
+    {
+        match TcpStream::connect_timeout(&socket_address, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// Send the password, cmus answers with an empty line on success,
+/// and closes the connection if the password is wrong.
+fn authenticate<S: Read + Write>(
+    stream: &mut S,
+    password: &str,
+) -> std::io::Result<bool> {
+    stream.write_all(format!("passwd {}\n", password).as_bytes())?;
+    stream.flush()?;
+    match read_answer(stream) {
+        Ok(answer) => Ok(answer == b"\n"),
+        Err(ref e)
+            if e.kind() == std::io::ErrorKind::ConnectionReset
+                || e.kind() == std::io::ErrorKind::BrokenPipe =>
+        {
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Send the `status` command and read the answer,
+/// which is terminated by an empty line.
+fn query_status<S: Read + Write>(mut stream: S) -> std::io::Result<Vec<u8>> {
+    stream.write_all(STATUS_COMMAND)?;
+    stream.flush()?;
+    read_answer(&mut stream)
+}
+
+/// Read until the answer's terminating empty line, or until EOF.
+fn read_answer<S: Read>(stream: &mut S) -> std::io::Result<Vec<u8>> {
+    let mut answer = Vec::new();
+    let mut buffer = [0; 4096];
+
+    loop {
+        let len = stream.read(&mut buffer)?;
+        if len == 0 {
+            break;
+        }
+        answer.extend_from_slice(&buffer[..len]);
+        if answer == b"\n" || answer.ends_with(b"\n\n") {
+            break;
+        }
+    }
+
+    Ok(answer)
+}
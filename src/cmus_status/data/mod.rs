@@ -1,49 +1,137 @@
+mod parser;
 mod playback_status;
+mod settings;
+mod tags;
 mod time;
 
 pub mod prelude {
     pub use super::CmusData;
     pub use super::CmusPlaybackStatus;
+    pub use super::{CmusAaaMode, CmusReplayGain, CmusSettings, CmusShuffle};
+    pub use super::{CmusDataParser, InvalidUtf8Mode, ParseMode};
+    pub use super::{CmusDate, CmusNumberTag};
     pub use super::{CmusTime, Seconds};
 }
 
+pub use parser::{CmusDataParser, InvalidUtf8Mode, ParseMode};
 pub use playback_status::CmusPlaybackStatus;
+pub use settings::{CmusAaaMode, CmusReplayGain, CmusSettings, CmusShuffle};
+pub use tags::{CmusDate, CmusNumberTag};
 pub use time::{CmusTime, Seconds};
 
 use crate::error::prelude::*;
 use std::collections::HashMap;
 use std::convert::TryFrom;
-use std::ops::Deref;
 use std::path::PathBuf;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CmusData {
     status:   CmusPlaybackStatus,
     file:     Option<PathBuf>,
+    stream:   Option<String>,
     time:     Option<CmusTime>,
     tags:     HashMap<String, String>,
     settings: CmusSettings,
+    extras:   HashMap<String, String>,
 }
 
 impl CmusData {
     pub fn get_title(&self) -> Option<String> {
-        self.file.as_ref().map(|file| {
+        self.file.as_ref().and_then(|file| {
             file.file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .replace("_", " ")
+                .map(|stem| stem.to_string_lossy().replace('_', " "))
         })
     }
 
+    pub fn get_file(&self) -> Option<&PathBuf> {
+        self.file.as_ref()
+    }
+
     pub fn get_status(&self) -> &CmusPlaybackStatus {
         &self.status
     }
 
+    /// Returns the stream's title, if an internet radio stream is playing.
+    pub fn get_stream(&self) -> Option<&String> {
+        self.stream.as_ref()
+    }
+
+    pub fn is_stream(&self) -> bool {
+        self.stream.is_some()
+    }
+
     pub fn get_tag(&self, tag_name: &str) -> Option<String> {
         self.tags.get(tag_name).cloned()
     }
 
+    /// Returns the tag value parsed as a number, such as `bpm`.
+    /// Values like `3/12` return their number, and dates their year.
+    /// Returns `None` if the tag doesn't exist or isn't a number.
+    pub fn get_tag_number(&self, tag_name: &str) -> Option<f64> {
+        self.tags.get(tag_name).and_then(|value| {
+            value
+                .trim()
+                .parse()
+                .ok()
+                .or_else(|| {
+                    CmusNumberTag::try_from(value.as_str())
+                        .ok()
+                        .map(|number_tag| number_tag.number as f64)
+                })
+                .or_else(|| {
+                    CmusDate::try_from(value.as_str())
+                        .ok()
+                        .map(|date| date.year as f64)
+                })
+        })
+    }
+
+    /// Returns the `tracknumber` tag, with the total number of tracks
+    /// from either `tracknumber` (`3/12`), `tracktotal` or `totaltracks`.
+    pub fn get_track_number(&self) -> Option<CmusNumberTag> {
+        self.get_number_tag("tracknumber", &["tracktotal", "totaltracks"])
+    }
+
+    /// Returns the `discnumber` tag, with the total number of discs
+    /// from either `discnumber` (`1/2`), `disctotal` or `totaldiscs`.
+    pub fn get_disc_number(&self) -> Option<CmusNumberTag> {
+        self.get_number_tag("discnumber", &["disctotal", "totaldiscs"])
+    }
+
+    /// Returns the `date` tag, falling back to `originaldate`.
+    pub fn get_date(&self) -> Option<CmusDate> {
+        ["date", "originaldate"].iter().find_map(|tag_name| {
+            self.tags
+                .get(*tag_name)
+                .and_then(|value| CmusDate::try_from(value.as_str()).ok())
+        })
+    }
+
+    pub fn get_year(&self) -> Option<u16> {
+        self.get_date().map(|date| date.year)
+    }
+
+    fn get_number_tag(
+        &self,
+        tag_name: &str,
+        total_tag_names: &[&str],
+    ) -> Option<CmusNumberTag> {
+        self.tags
+            .get(tag_name)
+            .and_then(|value| CmusNumberTag::try_from(value.as_str()).ok())
+            .map(|mut number_tag| {
+                if number_tag.total.is_none() {
+                    number_tag.total =
+                        total_tag_names.iter().find_map(|total_tag_name| {
+                            self.tags
+                                .get(*total_tag_name)
+                                .and_then(|total| total.trim().parse().ok())
+                        });
+                }
+                number_tag
+            })
+    }
+
     pub fn has_tag(&self, tag_name: &str) -> bool {
         self.tags.contains_key(tag_name)
     }
@@ -55,97 +143,22 @@ impl CmusData {
     pub fn get_time(&self) -> &Option<CmusTime> {
         &self.time
     }
+
+    pub fn get_settings(&self) -> &CmusSettings {
+        &self.settings
+    }
+
+    /// Returns the raw value of an unknown line from `cmus-remote -Q`,
+    /// collected with `ParseMode::Lenient`.
+    pub fn get_raw(&self, key: &str) -> Option<&String> {
+        self.extras.get(key)
+    }
 }
 
 impl TryFrom<String> for CmusData {
     type Error = Error;
 
     fn try_from(string: String) -> Result<Self, Self::Error> {
-        const STATUS_NAME: &str = "status";
-        const FILE_NAME: &str = "file";
-        const TIME_DURATION_NAME: &str = "duration";
-        const TIME_POSITION_NAME: &str = "position";
-        const TAG_NAME: &str = "tag";
-        const SETTINGS_NAME: &str = "set";
-
-        let mut status = None;
-        let mut file = None;
-        let mut time_duration = None;
-        let mut time_position = None;
-        let mut tags = HashMap::new();
-
-        for line in string.trim().split("\n") {
-            let words = line.split_whitespace().collect::<Vec<&str>>();
-            let data_name =
-                words.first().ok_or(Error::CmusParseError(format!(
-                    "Couldn't get data identifier name from `cmus-remote -Q` \
-                     (first word per line)\nOutput:\n{}",
-                    string
-                )))?;
-            let data_words = words
-                .iter()
-                .skip(1)
-                .map(Deref::deref)
-                .collect::<Vec<&str>>();
-            let data_line = data_words.join(" ");
-
-            match *data_name {
-                STATUS_NAME => {
-                    status =
-                        Some(CmusPlaybackStatus::try_from(data_line.as_str())?);
-                }
-                FILE_NAME => {
-                    file = Some(PathBuf::from(data_line.as_str()));
-                }
-                TIME_DURATION_NAME => {
-                    time_duration = Some(data_line.parse::<Seconds>().or(
-                        Err(Error::CouldntParseTimeToNumber(line.into())),
-                    )?);
-                }
-                TIME_POSITION_NAME => {
-                    time_position = Some(data_line.parse::<Seconds>().or(
-                        Err(Error::CouldntParseTimeToNumber(line.into())),
-                    )?);
-                }
-                TAG_NAME => {
-                    let tag_name = data_words
-                        .get(0)
-                        .ok_or(Error::CmusExpectDataArguments(1, line.into()))?
-                        .to_string();
-                    let tag_value = data_words
-                        .iter()
-                        .skip(1)
-                        .map(Deref::deref)
-                        .collect::<Vec<&str>>()
-                        .join(" ");
-                    tags.insert(tag_name, tag_value);
-                }
-                SETTINGS_NAME => {
-                    // TODO
-                }
-                _ => return Err(Error::CmusUnknownData(line.into())),
-            }
-        }
-
-        Ok(Self {
-            status: status.ok_or(Error::CmusMissingData(STATUS_NAME.into()))?,
-            file: file,
-            time: time_duration
-                .and_then(|duration| {
-                    time_position
-                        .and_then(|position| Some((duration, position)))
-                })
-                .map(|(duration, position)| CmusTime {
-                    duration: duration,
-                    position: position,
-                }),
-            tags,
-            // TODO
-            settings: CmusSettings {},
-        })
+        CmusDataParser::default().parse(string.as_str())
     }
 }
-
-// TODO
-#[derive(Debug)]
-pub struct CmusSettings {}
@@ -0,0 +1,259 @@
+use super::*;
+use std::borrow::Cow;
+
+/// How to handle output from `cmus-remote -Q`, which can't be parsed.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub enum ParseMode {
+    /// Any unknown or invalid line is an error.
+    #[default]
+    Strict,
+    /// Unknown lines are collected as raw key/value pairs,
+    /// invalid lines are skipped.
+    Lenient,
+}
+
+/// How to handle text from `cmus-remote -Q`, which isn't valid UTF-8,
+/// for example tags of Latin-1 encoded files.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub enum InvalidUtf8Mode {
+    /// Invalid characters are replaced with `U+FFFD`.
+    #[default]
+    Replace,
+    /// Invalid lines are an `Error::CmusInvalidUtf8`.
+    /// The `file` path is never decoded, so it may still be invalid UTF-8.
+    Error,
+}
+
+#[derive(Default)]
+pub struct CmusDataParser {
+    mode:         ParseMode,
+    invalid_utf8: InvalidUtf8Mode,
+    verbose:      bool,
+}
+
+impl CmusDataParser {
+    pub fn mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn invalid_utf8(mut self, invalid_utf8: InvalidUtf8Mode) -> Self {
+        self.invalid_utf8 = invalid_utf8;
+        self
+    }
+
+    /// Print warnings about skipped lines to stderr (`ParseMode::Lenient`).
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    pub fn parse(&self, output: &str) -> MyResult<CmusData> {
+        self.parse_bytes(output.as_bytes())
+    }
+
+    /// Parse the raw output, which may contain invalid UTF-8.
+    /// The `file` path is kept as is.
+    pub fn parse_bytes(&self, output: &[u8]) -> MyResult<CmusData> {
+        let mut data = ParsedData::default();
+
+        for line in output.split(|&byte| byte == b'\n') {
+            let line = trim_line(line);
+            if line.is_empty() {
+                continue;
+            }
+            if let Err(e) = self.parse_line(&mut data, output, line) {
+                match self.mode {
+                    ParseMode::Strict => return Err(e),
+                    ParseMode::Lenient => self.warn(&e),
+                }
+            }
+        }
+
+        let time_position = data.time_position;
+
+        Ok(CmusData {
+            status:   data
+                .status
+                .ok_or(Error::CmusMissingData(STATUS_NAME.into()))?,
+            file:     data.file,
+            stream:   data.stream,
+            time:     data
+                .time_duration
+                .and_then(|duration| {
+                    time_position.map(|position| (duration, position))
+                })
+                .map(|(duration, position)| CmusTime { duration, position }),
+            tags:     data.tags,
+            settings: data.settings,
+            extras:   data.extras,
+        })
+    }
+
+    fn parse_line(
+        &self,
+        data: &mut ParsedData,
+        output: &[u8],
+        raw_line: &[u8],
+    ) -> MyResult<()> {
+        // The `file` path is kept as raw bytes, so it's split off
+        // before the rest of the line is decoded.
+        if let Some(path) = strip_data_name(raw_line, FILE_NAME) {
+            if path.is_empty() {
+                return Err(Error::CmusExpectDataArguments(
+                    1,
+                    String::from_utf8_lossy(raw_line).into_owned(),
+                ));
+            }
+            data.file = Some(path_from_bytes(path));
+            return Ok(());
+        }
+
+        let line = self.decode(raw_line)?;
+        let line = line.as_ref();
+
+        // Only the data name (and tag or setting name) are split off,
+        // the rest of the line is kept as is, including any whitespace.
+        let (data_name, data_line) = split_first_word(line);
+        if data_name.is_empty() {
+            return Err(Error::CmusParseError(format!(
+                "Couldn't get data identifier name from `cmus-remote -Q` \
+                 (first word per line)\nOutput:\n{}",
+                String::from_utf8_lossy(output)
+            )));
+        }
+
+        match data_name {
+            STATUS_NAME => {
+                data.status =
+                    Some(CmusPlaybackStatus::try_from(data_line.trim())?);
+            }
+            STREAM_NAME => {
+                data.stream = Some(data_line.to_string());
+            }
+            TIME_DURATION_NAME => {
+                // Streams have an unknown duration of `-1`.
+                let duration = data_line
+                    .trim()
+                    .parse::<i64>()
+                    .or(Err(Error::CouldntParseTimeToNumber(line.into())))?;
+                data.time_duration = Some(if duration < 0 {
+                    None
+                } else {
+                    Some(duration as Seconds)
+                });
+            }
+            TIME_POSITION_NAME => {
+                data.time_position =
+                    Some(data_line.trim().parse::<Seconds>().or(Err(
+                        Error::CouldntParseTimeToNumber(line.into()),
+                    ))?);
+            }
+            TAG_NAME => {
+                let (tag_name, tag_value) = split_first_word(data_line);
+                if tag_name.is_empty() {
+                    return Err(Error::CmusExpectDataArguments(1, line.into()));
+                }
+                data.tags
+                    .insert(tag_name.to_string(), tag_value.to_string());
+            }
+            SETTINGS_NAME => {
+                let (setting_name, setting_value) = split_first_word(data_line);
+                if setting_name.is_empty() {
+                    return Err(Error::CmusExpectDataArguments(1, line.into()));
+                }
+                data.settings.set(setting_name, setting_value);
+            }
+            _ => match self.mode {
+                ParseMode::Strict => {
+                    return Err(Error::CmusUnknownData(line.into()))
+                }
+                ParseMode::Lenient => {
+                    self.warn(&Error::CmusUnknownData(line.into()));
+                    data.extras
+                        .insert(data_name.to_string(), data_line.to_string());
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    fn decode<'a>(&self, line: &'a [u8]) -> MyResult<Cow<'a, str>> {
+        match self.invalid_utf8 {
+            InvalidUtf8Mode::Replace => Ok(String::from_utf8_lossy(line)),
+            InvalidUtf8Mode::Error => {
+                std::str::from_utf8(line).map(Cow::Borrowed).map_err(|_| {
+                    Error::CmusInvalidUtf8(
+                        String::from_utf8_lossy(line).into_owned(),
+                    )
+                })
+            }
+        }
+    }
+
+    fn warn(&self, error: &Error) {
+        if self.verbose {
+            eprintln!("WARNING: {}", error.message());
+        }
+    }
+}
+
+/// Removes a trailing `\r` (CRLF line endings) and leading whitespace.
+/// Trailing whitespace is kept, as it may be part of a tag value.
+fn trim_line(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    let start = line
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(line.len());
+    &line[start..]
+}
+
+/// Returns the rest of the raw line, if its first word is the `data_name`.
+fn strip_data_name<'a>(line: &'a [u8], data_name: &str) -> Option<&'a [u8]> {
+    let rest = line.strip_prefix(data_name.as_bytes())?;
+    match rest.first() {
+        None => Some(rest),
+        Some(b' ') | Some(b'\t') => Some(&rest[1..]),
+        Some(_) => None,
+    }
+}
+
+/// Splits the given line at the first space or tab.
+/// The rest of the line is returned unchanged.
+fn split_first_word(line: &str) -> (&str, &str) {
+    line.split_once([' ', '\t']).unwrap_or((line, ""))
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+const STATUS_NAME: &str = "status";
+const FILE_NAME: &str = "file";
+const STREAM_NAME: &str = "stream";
+const TIME_DURATION_NAME: &str = "duration";
+const TIME_POSITION_NAME: &str = "position";
+const TAG_NAME: &str = "tag";
+const SETTINGS_NAME: &str = "set";
+
+#[derive(Default)]
+struct ParsedData {
+    status:        Option<CmusPlaybackStatus>,
+    file:          Option<PathBuf>,
+    stream:        Option<String>,
+    time_duration: Option<Option<Seconds>>,
+    time_position: Option<Seconds>,
+    tags:          HashMap<String, String>,
+    settings:      CmusSettings,
+    extras:        HashMap<String, String>,
+}
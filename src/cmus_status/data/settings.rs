@@ -0,0 +1,172 @@
+use crate::error::prelude::*;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// The `set ...` lines from `cmus-remote -Q`.
+/// Settings which `cmus` didn't print are `None`.
+#[derive(Debug, Clone, Default)]
+pub struct CmusSettings {
+    pub aaa_mode:          Option<CmusAaaMode>,
+    pub continue_playback: Option<bool>,
+    pub play_library:      Option<bool>,
+    pub play_sorted:       Option<bool>,
+    pub repeat:            Option<bool>,
+    pub repeat_current:    Option<bool>,
+    pub shuffle:           Option<CmusShuffle>,
+    pub softvol:           Option<bool>,
+    pub replaygain:        Option<CmusReplayGain>,
+    pub vol_left:          Option<u8>,
+    pub vol_right:         Option<u8>,
+    raw:                   HashMap<String, String>,
+}
+
+impl CmusSettings {
+    /// Parse and store the setting with the given name and value.
+    /// Unknown setting names are only stored as raw values,
+    /// and values which can't be parsed leave the typed setting `None`,
+    /// so newer `cmus` versions don't break the status line.
+    pub fn set(&mut self, name: &str, value: &str) {
+        const AAA_MODE: &str = "aaa_mode";
+        const CONTINUE: &str = "continue";
+        const PLAY_LIBRARY: &str = "play_library";
+        const PLAY_SORTED: &str = "play_sorted";
+        const REPEAT: &str = "repeat";
+        const REPEAT_CURRENT: &str = "repeat_current";
+        const SHUFFLE: &str = "shuffle";
+        const SOFTVOL: &str = "softvol";
+        const REPLAYGAIN: &str = "replaygain";
+        const VOL_LEFT: &str = "vol_left";
+        const VOL_RIGHT: &str = "vol_right";
+
+        match name {
+            AAA_MODE => self.aaa_mode = CmusAaaMode::try_from(value).ok(),
+            CONTINUE => self.continue_playback = parse_bool(value),
+            PLAY_LIBRARY => self.play_library = parse_bool(value),
+            PLAY_SORTED => self.play_sorted = parse_bool(value),
+            REPEAT => self.repeat = parse_bool(value),
+            REPEAT_CURRENT => self.repeat_current = parse_bool(value),
+            SHUFFLE => self.shuffle = CmusShuffle::try_from(value).ok(),
+            SOFTVOL => self.softvol = parse_bool(value),
+            REPLAYGAIN => {
+                self.replaygain = CmusReplayGain::try_from(value).ok()
+            }
+            VOL_LEFT => self.vol_left = parse_volume(value),
+            VOL_RIGHT => self.vol_right = parse_volume(value),
+            _ => (),
+        }
+
+        self.raw.insert(name.to_string(), value.to_string());
+    }
+
+    /// Returns the setting's value as it was printed by `cmus`.
+    pub fn get_raw(&self, name: &str) -> Option<&String> {
+        self.raw.get(name)
+    }
+
+    pub fn is_shuffle(&self) -> bool {
+        self.shuffle
+            .as_ref()
+            .map(|shuffle| shuffle != &CmusShuffle::Off)
+            .unwrap_or(false)
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_volume(value: &str) -> Option<u8> {
+    value.parse().ok().filter(|volume| *volume <= 100)
+}
+
+/// The `aaa_mode` setting; which tracks are played from the library.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum CmusAaaMode {
+    All,
+    Artist,
+    Album,
+}
+
+impl TryFrom<&str> for CmusAaaMode {
+    type Error = Error;
+
+    fn try_from(value: &str) -> MyResult<Self> {
+        match value {
+            "all" => Ok(CmusAaaMode::All),
+            "artist" => Ok(CmusAaaMode::Artist),
+            "album" => Ok(CmusAaaMode::Album),
+            _ => Err(Error::CmusInvalidSetting(format!("aaa_mode {}", value))),
+        }
+    }
+}
+
+impl fmt::Display for CmusAaaMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CmusAaaMode::All => "all",
+                CmusAaaMode::Artist => "artist",
+                CmusAaaMode::Album => "album",
+            }
+        )
+    }
+}
+
+/// The `shuffle` setting.
+/// Older `cmus` versions print `true`/`false`,
+/// newer ones print `off`/`tracks`/`albums`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum CmusShuffle {
+    Off,
+    Tracks,
+    Albums,
+}
+
+impl TryFrom<&str> for CmusShuffle {
+    type Error = Error;
+
+    fn try_from(value: &str) -> MyResult<Self> {
+        match value {
+            "off" | "false" => Ok(CmusShuffle::Off),
+            "tracks" | "true" => Ok(CmusShuffle::Tracks),
+            "albums" => Ok(CmusShuffle::Albums),
+            _ => Err(Error::CmusInvalidSetting(format!("shuffle {}", value))),
+        }
+    }
+}
+
+/// The `replaygain` setting.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum CmusReplayGain {
+    Disabled,
+    Track,
+    Album,
+    TrackPreferred,
+    AlbumPreferred,
+    Smart,
+}
+
+impl TryFrom<&str> for CmusReplayGain {
+    type Error = Error;
+
+    fn try_from(value: &str) -> MyResult<Self> {
+        match value {
+            "disabled" => Ok(CmusReplayGain::Disabled),
+            "track" => Ok(CmusReplayGain::Track),
+            "album" => Ok(CmusReplayGain::Album),
+            "track-preferred" => Ok(CmusReplayGain::TrackPreferred),
+            "album-preferred" => Ok(CmusReplayGain::AlbumPreferred),
+            "smart" => Ok(CmusReplayGain::Smart),
+            _ => {
+                Err(Error::CmusInvalidSetting(format!("replaygain {}", value)))
+            }
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::prelude::*;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// A number tag with an optional total,
+/// such as `tracknumber` or `discnumber`.
+/// Parses values like `3`, `03` and `3/12`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CmusNumberTag {
+    pub number: u32,
+    pub total:  Option<u32>,
+}
+
+impl TryFrom<&str> for CmusNumberTag {
+    type Error = Error;
+
+    fn try_from(value: &str) -> MyResult<Self> {
+        let invalid = || Error::CmusInvalidTag(value.to_string());
+        let mut split = value.trim().splitn(2, '/');
+        let number = split
+            .next()
+            .and_then(|number| number.trim().parse().ok())
+            .ok_or_else(invalid)?;
+        let total = match split.next().map(str::trim) {
+            Some(total) if !total.is_empty() => {
+                Some(total.parse().map_err(|_| invalid())?)
+            }
+            _ => None,
+        };
+        Ok(Self { number, total })
+    }
+}
+
+/// A date tag, such as `date` or `originaldate`.
+/// Parses values like `2011`, `2011-05` and `2011-05-03`,
+/// ignoring any time after the date.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CmusDate {
+    pub year:  u16,
+    pub month: Option<u8>,
+    pub day:   Option<u8>,
+}
+
+impl TryFrom<&str> for CmusDate {
+    type Error = Error;
+
+    fn try_from(value: &str) -> MyResult<Self> {
+        let invalid = || Error::CmusInvalidTag(value.to_string());
+        let date = value.trim().split(['T', ' ']).next().unwrap_or_default();
+        let mut split = date.split('-');
+
+        let year = split
+            .next()
+            .filter(|year| year.len() == 4)
+            .and_then(|year| year.parse().ok())
+            .ok_or_else(invalid)?;
+        let month = split
+            .next()
+            .map(|month| month.parse().ok().filter(|m| (1..=12).contains(m)))
+            .map(|month| month.ok_or_else(invalid))
+            .transpose()?;
+        let day = split
+            .next()
+            .map(|day| day.parse().ok().filter(|d| (1..=31).contains(d)))
+            .map(|day| day.ok_or_else(invalid))
+            .transpose()?;
+
+        if split.next().is_some() {
+            Err(invalid())
+        } else {
+            Ok(Self { year, month, day })
+        }
+    }
+}
+
+impl fmt::Display for CmusDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+            if let Some(day) = self.day {
+                write!(f, "-{:02}", day)?;
+            }
+        }
+        Ok(())
+    }
+}
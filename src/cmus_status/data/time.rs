@@ -1,55 +1,27 @@
-use crate::error::prelude::*;
-use std::convert::TryFrom;
-use std::fmt;
-
 pub type Seconds = u32;
 
-#[derive(Debug, PartialEq, Deserialize)]
-pub enum CmusPlaybackStatus {
-    Playing,
-    Paused,
-    Stopped,
-}
-
-impl TryFrom<&str> for CmusPlaybackStatus {
-    type Error = Error;
-
-    fn try_from(status_str: &str) -> MyResult<Self> {
-        const STATUS_PLAYING: &str = "playing";
-        const STATUS_PAUSED: &str = "paused";
-        const STATUS_STOPPED: &str = "stopped";
-
-        match status_str.to_lowercase().as_str() {
-            STATUS_PLAYING => Ok(CmusPlaybackStatus::Playing),
-            STATUS_PAUSED => Ok(CmusPlaybackStatus::Paused),
-            STATUS_STOPPED => Ok(CmusPlaybackStatus::Stopped),
-            s => Err(Error::CmusUnknownStatus(s.into())),
-        }
-    }
-}
-
-impl fmt::Display for CmusPlaybackStatus {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                CmusPlaybackStatus::Playing => "Playing",
-                CmusPlaybackStatus::Paused => "Paused",
-                CmusPlaybackStatus::Stopped => "Stopped",
-            }
-        )
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CmusTime {
-    pub duration: Seconds,
+    /// `None` if the duration is unknown, for example for
+    /// internet radio streams.
+    pub duration: Option<Seconds>,
     pub position: Seconds,
 }
 
 impl CmusTime {
-    pub fn completion_percentage(&self) -> f32 {
-        self.position as f32 / self.duration as f32
+    /// Returns the completion from `0.0` to `1.0`.
+    /// Returns `None` if the duration is unknown or zero.
+    pub fn completion_percentage(&self) -> Option<f32> {
+        self.duration
+            .filter(|duration| *duration > 0)
+            .map(|duration| {
+                (self.position as f32 / duration as f32).clamp(0.0, 1.0)
+            })
+    }
+
+    /// Returns `None` if the duration is unknown.
+    pub fn remaining(&self) -> Option<Seconds> {
+        self.duration
+            .map(|duration| duration.saturating_sub(self.position))
     }
 }
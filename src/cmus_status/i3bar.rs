@@ -0,0 +1,118 @@
+//! The i3bar protocol, understood by i3bar and swaybar.
+//! Prints the protocol's header, followed by an infinite JSON array,
+//! with one array of blocks per update.
+//! See <https://i3wm.org/docs/i3bar-protocol.html>.
+
+use super::output::{EscapeTarget, StyleTarget};
+use super::source::{CmusSource, SourceConfig};
+use super::{build_status_output, get_cmus_data_from, StatusOptions};
+use crate::config::{default_i3bar_block_name, Config, I3barBlockConfig};
+use crate::error::prelude::*;
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+const HEADER: &str = r#"{"version":1}"#;
+const MIN_INTERVAL: f32 = 0.1;
+
+/// Prints the cmus status as i3bar blocks, until stdout is closed.
+pub fn run_i3bar(options: StatusOptions) -> MyResult<()> {
+    let config = crate::config::get_config()?;
+    // stdin is at its end after the first update.
+    if options.source_config(&config) == &SourceConfig::Stdin {
+        return Err(Error::I3barStdinSource);
+    }
+    let mut source = options.create_source(&config)?;
+    let blocks = if config.i3bar.blocks.is_empty() {
+        vec![I3barBlockConfig {
+            name:         default_i3bar_block_name(),
+            format:       config.format.clone(),
+            short_format: None,
+            color:        Default::default(),
+        }]
+    } else {
+        config.i3bar.blocks.clone()
+    };
+    let interval =
+        Duration::from_secs_f32(config.i3bar.interval.max(MIN_INTERVAL));
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    // Writing only fails once the bar has closed stdout, so just stop then.
+    if writeln!(stdout, "{}\n[", HEADER).is_err() {
+        return Ok(());
+    }
+
+    loop {
+        // Print no blocks while cmus isn't running.
+        let line = get_blocks_json(source.as_mut(), &config, &blocks)
+            .unwrap_or_else(|e| {
+                if config.verbose {
+                    eprintln!("WARNING: {}", e.message());
+                }
+                "[]".to_string()
+            });
+        if writeln!(stdout, "{},", line)
+            .and_then(|_| stdout.flush())
+            .is_err()
+        {
+            return Ok(());
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn get_blocks_json(
+    source: &mut dyn CmusSource,
+    config: &Config,
+    blocks: &[I3barBlockConfig],
+) -> MyResult<String> {
+    let cmus_data = get_cmus_data_from(source, config)?;
+    let is_pango = config.style_target == Some(StyleTarget::Pango)
+        || config.escape == Some(EscapeTarget::Pango);
+    // Pango markup needs cmus' data to be escaped, even without `escape`.
+    let escape = match &config.escape {
+        None if is_pango => Some(EscapeTarget::Pango),
+        escape => escape.clone(),
+    };
+    let json = |text: &str| EscapeTarget::Json.escape(text);
+
+    let mut blocks_json = Vec::new();
+    for block in blocks {
+        let full_text = build_status_output(
+            cmus_data.clone(),
+            block.format.clone(),
+            config,
+            escape.clone(),
+        )?
+        .to_string();
+        // i3bar still draws a separator for empty blocks.
+        if full_text.is_empty() {
+            continue;
+        }
+
+        let mut fields = vec![
+            format!(r#""name":"{}""#, json(&block.name)),
+            format!(r#""full_text":"{}""#, json(&full_text)),
+        ];
+        if let Some(short_format) = &block.short_format {
+            let short_text = build_status_output(
+                cmus_data.clone(),
+                short_format.clone(),
+                config,
+                escape.clone(),
+            )?
+            .to_string();
+            fields.push(format!(r#""short_text":"{}""#, json(&short_text)));
+        }
+        if let Some(color) = block.color.to_hex(&config.theme) {
+            fields.push(format!(r#""color":"{}""#, color));
+        }
+        if is_pango {
+            fields.push(r#""markup":"pango""#.to_string());
+        }
+        blocks_json.push(format!("{{{}}}", fields.join(",")));
+    }
+
+    Ok(format!("[{}]", blocks_json.join(",")))
+}
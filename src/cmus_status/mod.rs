@@ -1,41 +1,133 @@
+pub mod client;
 pub mod data;
+pub mod i3bar;
 pub mod output;
+pub mod source;
+pub mod waybar;
 
+use crate::config::Config;
 use crate::error::prelude::*;
 use std::convert::TryFrom;
-use std::process::Command;
 
-use data::CmusData;
-use output::StatusOutput;
+use data::{CmusData, CmusDataParser};
+use output::{EscapeTarget, Format, StatusOutput};
+use source::{CmusSource, SourceConfig};
 
-pub fn print_cmus_status() -> MyResult<()> {
-    let cmus_status = get_cmus_status()?;
-    println!("{}", cmus_status);
+/// Options for the `status` command, overwriting the config.
+#[derive(Default)]
+pub struct StatusOptions {
+    pub source: Option<SourceConfig>,
+    pub output: OutputMode,
+}
+
+impl StatusOptions {
+    /// Creates the `CmusSource` from the `source` option,
+    /// or from the config's `source`.
+    pub fn create_source(
+        &self,
+        config: &Config,
+    ) -> MyResult<Box<dyn CmusSource>> {
+        self.source_config(config).create_source(config)
+    }
+
+    /// Returns the `source` option, or the config's `source`.
+    pub fn source_config<'a>(&'a self, config: &'a Config) -> &'a SourceConfig {
+        self.source.as_ref().unwrap_or(&config.source)
+    }
+}
+
+/// How the `status` command prints the status,
+/// set with the `--output` command-line option.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum OutputMode {
+    /// The config's format as plain text.
+    #[default]
+    Text,
+    /// A JSON object for Waybar's custom modules.
+    Waybar,
+}
+
+impl TryFrom<&str> for OutputMode {
+    type Error = Error;
+
+    fn try_from(name: &str) -> MyResult<Self> {
+        match name {
+            "text" => Ok(OutputMode::Text),
+            "waybar" => Ok(OutputMode::Waybar),
+            _ => Err(Error::InvalidOutput(name.to_string())),
+        }
+    }
+}
+
+pub fn print_cmus_status(options: StatusOptions) -> MyResult<()> {
+    match options.output {
+        OutputMode::Text => {
+            println!("{}", get_cmus_status_with(options)?);
+        }
+        OutputMode::Waybar => {
+            let config = crate::config::get_config()?;
+            let mut source = options.create_source(&config)?;
+            println!(
+                "{}",
+                waybar::get_waybar_json_from(source.as_mut(), &config)?
+            );
+        }
+    }
     Ok(())
 }
 
 pub fn get_cmus_status() -> MyResult<StatusOutput> {
-    let output = get_cmus_remote_output()?;
-    let cmus_data = CmusData::try_from(output)?;
+    get_cmus_status_with(StatusOptions::default())
+}
+
+pub fn get_cmus_status_with(options: StatusOptions) -> MyResult<StatusOutput> {
     let config = crate::config::get_config()?;
+    let mut source = options.create_source(&config)?;
+    get_cmus_status_from(source.as_mut(), config)
+}
+
+/// Builds the `StatusOutput` from the given `CmusSource`'s output,
+/// with the given config's format and parse settings.
+pub fn get_cmus_status_from(
+    source: &mut dyn CmusSource,
+    config: Config,
+) -> MyResult<StatusOutput> {
+    let cmus_data = get_cmus_data_from(source, &config)?;
+    build_status_output(
+        cmus_data,
+        config.format.clone(),
+        &config,
+        config.escape.clone(),
+    )
+}
+
+/// Parses the given `CmusSource`'s output with the config's parse settings.
+pub fn get_cmus_data_from(
+    source: &mut dyn CmusSource,
+    config: &Config,
+) -> MyResult<CmusData> {
+    let output = source.get_output()?;
+    CmusDataParser::default()
+        .mode(config.parse_mode.clone())
+        .invalid_utf8(config.invalid_utf8.clone())
+        .verbose(config.verbose)
+        .parse_bytes(&output)
+}
+
+/// Builds the `StatusOutput` for the given format,
+/// escaping text derived from cmus' data with the given `escape`,
+/// and with the config's style settings.
+pub fn build_status_output(
+    cmus_data: CmusData,
+    format: Format,
+    config: &Config,
+    escape: Option<EscapeTarget>,
+) -> MyResult<StatusOutput> {
     StatusOutput::builder()
         .data(cmus_data)
-        .format(config.format)
+        .format(format)
+        .escape(escape)
+        .style(config.style_target.clone())
+        .theme(config.theme.clone())
         .build()
 }
-
-fn get_cmus_remote_output() -> MyResult<String> {
-    match Command::new("cmus-remote").arg("-Q").output() {
-        Ok(output) => {
-            if output.status.success() {
-                Ok(String::from_utf8(output.stdout).unwrap())
-            } else {
-                Err(Error::CmusError {
-                    status: output.status,
-                    stderr: String::from_utf8(output.stderr).unwrap(),
-                })
-            }
-        }
-        Err(_) => Err(Error::CmusNotInstalled),
-    }
-}
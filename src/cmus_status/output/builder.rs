@@ -4,6 +4,9 @@ use super::*;
 pub struct StatusOutputBuilder {
     data:   Option<CmusData>,
     format: Option<Format>,
+    escape: Option<EscapeTarget>,
+    style:  Option<StyleTarget>,
+    theme:  Theme,
 }
 
 impl StatusOutputBuilder {
@@ -17,10 +20,28 @@ impl StatusOutputBuilder {
         self
     }
 
+    pub fn escape(mut self, escape: Option<EscapeTarget>) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    pub fn style(mut self, style: Option<StyleTarget>) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
     pub fn build(self) -> MyResult<StatusOutput> {
         Ok(StatusOutput {
             data:   self.data.ok_or(Error::CmusStatusNoData)?,
-            format: self.format.unwrap_or_else(Default::default),
+            format: self.format.unwrap_or_default(),
+            escape: self.escape,
+            style:  self.style,
+            theme:  self.theme,
         })
     }
 }
@@ -0,0 +1,82 @@
+/// What to escape text for, used by the `Escape` `FormatPart`
+/// and the `escape` config key.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum EscapeTarget {
+    /// Escapes `&<>"'` with `htmlescape::encode_minimal`.
+    Html,
+    /// Escapes `&<>"'` with the entities Pango markup understands.
+    Pango,
+    /// Escapes the text for use inside a JSON string,
+    /// without adding the surrounding quotes.
+    Json,
+    /// Quotes the text with single quotes for POSIX shells,
+    /// unless it only consists of safe characters.
+    Shell,
+    /// Doubles `#`, so tmux doesn't interpret it as a format.
+    Tmux,
+    /// Doubles `%`, so lemonbar doesn't interpret it as a format.
+    Lemonbar,
+    /// Doubles `%`, so polybar doesn't interpret it as a format tag.
+    Polybar,
+}
+
+impl EscapeTarget {
+    pub fn escape(&self, text: &str) -> String {
+        match self {
+            EscapeTarget::Html => htmlescape::encode_minimal(text),
+            EscapeTarget::Pango => escape_pango(text),
+            EscapeTarget::Json => escape_json(text),
+            EscapeTarget::Shell => escape_shell(text),
+            EscapeTarget::Tmux => text.replace('#', "##"),
+            EscapeTarget::Lemonbar | EscapeTarget::Polybar => {
+                text.replace('%', "%%")
+            }
+        }
+    }
+}
+
+fn escape_pango(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let mut buf = [0; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    escaped.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_shell(text: &str) -> String {
+    let is_safe =
+        |c: char| c.is_ascii_alphanumeric() || "-_./,:@+=%".contains(c);
+    if !text.is_empty() && text.chars().all(is_safe) {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+}
@@ -0,0 +1,480 @@
+//! Parses the textual form of `FormatExpression`s, such as
+//! `status == playing && (has(artist) || stream)`.
+//!
+//! Grammar, from lowest to highest precedence:
+//!   - `a || b`  any of the expressions is `true`
+//!   - `a && b`  all of the expressions are `true`
+//!   - `!a`      inverts the expression
+//!   - `(a)`     grouping
+//!   - atoms:
+//!       - `true`, `false`
+//!       - `stream`, `has_time`, `shuffle`, `repeat`, `repeat_current`,
+//!         `continue`, `play_library`, `play_sorted`, `softvol`
+//!       - `status == playing`, `status != stopped`
+//!       - `aaa_mode == album`, `aaa_mode != all`
+//!       - `has(artist)`, `ext(flac)`, `file("**/*.flac")`
+//!       - `tag(genre) == "Rock"`, `tag(genre) != Rock`,
+//!         `tag(genre) =~ "(?i)^rock"`, `tag(date) < 1980`
+//!       - `position >= 30`, `remaining < 10`, `duration > 5`,
+//!         `percent > 50`
+
+use std::convert::TryFrom;
+
+use super::{CompareOp, FormatExpression};
+use super::{FormatGlob, FormatRegex};
+use crate::cmus_status::data::{CmusAaaMode, CmusPlaybackStatus, Seconds};
+use crate::error::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(f64),
+    Op(&'static str),
+    OpenParen,
+    CloseParen,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(ident) => format!("`{}`", ident),
+            Token::Str(s) => format!("{:?}", s),
+            Token::Number(n) => format!("`{}`", n),
+            Token::Op(op) => format!("`{}`", op),
+            Token::OpenParen => "`(`".to_string(),
+            Token::CloseParen => "`)`".to_string(),
+        }
+    }
+}
+
+/// Operators, longer operators first.
+const OPERATORS: &[&str] =
+    &["&&", "||", "==", "!=", "=~", "<=", ">=", "<", ">", "!"];
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    index:  usize,
+    len:    usize,
+}
+
+impl TryFrom<&str> for FormatExpression {
+    type Error = Error;
+
+    fn try_from(text: &str) -> MyResult<Self> {
+        let invalid = |(position, message): (usize, String)| {
+            Error::InvalidExpression(
+                text.to_string(),
+                format!("at position {}: {}", position + 1, message),
+            )
+        };
+        let tokens = tokenize(text).map_err(invalid)?;
+        let mut parser = Parser {
+            tokens,
+            index: 0,
+            len: text.len(),
+        };
+        let expression = parser.parse_or().map_err(invalid)?;
+        match parser.next() {
+            None => Ok(expression),
+            Some((position, token)) => Err(invalid((
+                position,
+                format!("unexpected {}", token.describe()),
+            ))),
+        }
+    }
+}
+
+type ParseResult<T> = Result<T, (usize, String)>;
+
+fn tokenize(text: &str) -> ParseResult<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some(&(position, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' {
+            chars.next();
+            tokens.push((position, Token::OpenParen));
+        } else if c == ')' {
+            chars.next();
+            tokens.push((position, Token::CloseParen));
+        } else if c == '"' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => {
+                        if let Some((_, escaped)) = chars.next() {
+                            s.push(escaped);
+                        }
+                    }
+                    Some((_, c)) => s.push(c),
+                    None => {
+                        return Err((position, "unterminated string".into()))
+                    }
+                }
+            }
+            tokens.push((position, Token::Str(s)));
+        } else if c.is_ascii_digit() || c == '.' || c == '-' {
+            let mut number = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' || c == '-' {
+                    number.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let number = number.parse().map_err(|_| {
+                (position, format!("invalid number `{}`", number))
+            })?;
+            tokens.push((position, Token::Number(number)));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut ident = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    ident.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push((position, Token::Ident(ident)));
+        } else if let Some(op) = OPERATORS
+            .iter()
+            .find(|op| text[position..].starts_with(*op))
+        {
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            tokens.push((position, Token::Op(op)));
+        } else {
+            return Err((position, format!("unexpected character `{}`", c)));
+        }
+    }
+
+    Ok(tokens)
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<(usize, Token)> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(_, token)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map(|(position, _)| *position)
+            .unwrap_or(self.len)
+    }
+
+    fn expected<T>(&self, expected: &str) -> ParseResult<T> {
+        let found = self
+            .peek()
+            .map(Token::describe)
+            .unwrap_or_else(|| "end of expression".to_string());
+        Err((
+            self.position(),
+            format!("expected {}, found {}", expected, found),
+        ))
+    }
+
+    fn consume(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> ParseResult<FormatExpression> {
+        let mut expressions = vec![self.parse_and()?];
+        while self.consume(&Token::Op("||")) {
+            expressions.push(self.parse_and()?);
+        }
+        Ok(if expressions.len() == 1 {
+            expressions.remove(0)
+        } else {
+            FormatExpression::Any(expressions)
+        })
+    }
+
+    fn parse_and(&mut self) -> ParseResult<FormatExpression> {
+        let mut expressions = vec![self.parse_unary()?];
+        while self.consume(&Token::Op("&&")) {
+            expressions.push(self.parse_unary()?);
+        }
+        Ok(if expressions.len() == 1 {
+            expressions.remove(0)
+        } else {
+            FormatExpression::All(expressions)
+        })
+    }
+
+    fn parse_unary(&mut self) -> ParseResult<FormatExpression> {
+        if self.consume(&Token::Op("!")) {
+            Ok(FormatExpression::Not(Box::new(self.parse_unary()?)))
+        } else if self.consume(&Token::OpenParen) {
+            let expression = self.parse_or()?;
+            if self.consume(&Token::CloseParen) {
+                Ok(expression)
+            } else {
+                self.expected("`)`")
+            }
+        } else {
+            self.parse_atom()
+        }
+    }
+
+    fn parse_atom(&mut self) -> ParseResult<FormatExpression> {
+        let position = self.position();
+        let ident = match self.peek() {
+            Some(Token::Ident(ident)) => ident.to_string(),
+            _ => return self.expected("an expression"),
+        };
+        self.index += 1;
+
+        match ident.as_str() {
+            "true" => Ok(FormatExpression::True),
+            "false" => Ok(FormatExpression::False),
+            "stream" => Ok(FormatExpression::IsStream),
+            "has_time" => Ok(FormatExpression::HasTime),
+            "shuffle" => Ok(FormatExpression::IsShuffle),
+            "repeat" => Ok(FormatExpression::IsRepeat),
+            "repeat_current" => Ok(FormatExpression::IsRepeatCurrent),
+            "continue" => Ok(FormatExpression::IsContinue),
+            "play_library" => Ok(FormatExpression::IsPlayLibrary),
+            "play_sorted" => Ok(FormatExpression::IsPlaySorted),
+            "softvol" => Ok(FormatExpression::IsSoftvol),
+
+            "status" => {
+                let equals = self.parse_equality()?;
+                let position = self.position();
+                let status = self.parse_word()?;
+                let status = CmusPlaybackStatus::try_from(status.as_str())
+                    .map_err(|_| {
+                        (
+                            position,
+                            format!(
+                                "unknown status `{}`, expected one of: \
+                                 playing, paused, stopped",
+                                status
+                            ),
+                        )
+                    })?;
+                Ok(negate_unless(equals, FormatExpression::IsStatus(status)))
+            }
+
+            "aaa_mode" => {
+                let equals = self.parse_equality()?;
+                let position = self.position();
+                let aaa_mode = self.parse_word()?;
+                let aaa_mode = CmusAaaMode::try_from(aaa_mode.as_str())
+                    .map_err(|_| {
+                        (
+                            position,
+                            format!(
+                                "unknown aaa_mode `{}`, expected one of: \
+                                 all, artist, album",
+                                aaa_mode
+                            ),
+                        )
+                    })?;
+                Ok(negate_unless(equals, FormatExpression::AaaMode(aaa_mode)))
+            }
+
+            "has" => Ok(FormatExpression::HasTag(self.parse_argument()?)),
+
+            "ext" => Ok(FormatExpression::HasExtension(self.parse_argument()?)),
+
+            "file" => {
+                let position = self.position();
+                let glob = FormatGlob::try_from(self.parse_argument()?)
+                    .map_err(|e| (position, e.message()))?;
+                Ok(FormatExpression::FileMatches(glob))
+            }
+
+            "tag" => {
+                let tag_name = self.parse_argument()?;
+                self.parse_tag_comparison(tag_name)
+            }
+
+            "position" => {
+                self.parse_time_op(">=")?;
+                Ok(FormatExpression::PositionAtLeast(self.parse_seconds()?))
+            }
+
+            "remaining" => {
+                self.parse_time_op("<")?;
+                Ok(FormatExpression::RemainingLessThan(self.parse_seconds()?))
+            }
+
+            "duration" => {
+                self.parse_time_op(">")?;
+                Ok(FormatExpression::DurationLongerThan(self.parse_seconds()?))
+            }
+
+            "percent" => {
+                self.parse_time_op(">")?;
+                Ok(FormatExpression::PercentAbove(self.parse_number()? as f32))
+            }
+
+            _ => Err((position, format!("unknown expression `{}`", ident))),
+        }
+    }
+
+    /// Returns `true` for `==` and `false` for `!=`.
+    fn parse_equality(&mut self) -> ParseResult<bool> {
+        if self.consume(&Token::Op("==")) {
+            Ok(true)
+        } else if self.consume(&Token::Op("!=")) {
+            Ok(false)
+        } else {
+            self.expected("`==` or `!=`")
+        }
+    }
+
+    fn parse_time_op(&mut self, op: &'static str) -> ParseResult<()> {
+        if self.consume(&Token::Op(op)) {
+            Ok(())
+        } else {
+            self.expected(&format!(
+                "`{}` (use `!` to invert the comparison)",
+                op
+            ))
+        }
+    }
+
+    /// An identifier or a string.
+    fn parse_word(&mut self) -> ParseResult<String> {
+        match self.peek() {
+            Some(Token::Ident(word)) | Some(Token::Str(word)) => {
+                let word = word.to_string();
+                self.index += 1;
+                Ok(word)
+            }
+            _ => self.expected("a name or a string"),
+        }
+    }
+
+    /// A word in parentheses, like `(artist)`.
+    fn parse_argument(&mut self) -> ParseResult<String> {
+        if !self.consume(&Token::OpenParen) {
+            return self.expected("`(`");
+        }
+        let argument = self.parse_word()?;
+        if self.consume(&Token::CloseParen) {
+            Ok(argument)
+        } else {
+            self.expected("`)`")
+        }
+    }
+
+    fn parse_number(&mut self) -> ParseResult<f64> {
+        match self.peek() {
+            Some(Token::Number(number)) => {
+                let number = *number;
+                self.index += 1;
+                Ok(number)
+            }
+            _ => self.expected("a number"),
+        }
+    }
+
+    fn parse_seconds(&mut self) -> ParseResult<Seconds> {
+        let position = self.position();
+        let number = self.parse_number()?;
+        if number >= 0.0 && number.fract() == 0.0 {
+            Ok(number as Seconds)
+        } else {
+            Err((
+                position,
+                format!("expected whole seconds, found `{}`", number),
+            ))
+        }
+    }
+
+    fn parse_tag_comparison(
+        &mut self,
+        tag_name: String,
+    ) -> ParseResult<FormatExpression> {
+        let position = self.position();
+        let op = match self.next() {
+            Some((_, Token::Op(op))) => op,
+            _ => {
+                self.index -= 1;
+                return self
+                    .expected("one of `==`, `!=`, `=~`, `<`, `<=`, `>`, `>=`");
+            }
+        };
+
+        let compare_op = match op {
+            "<" => Some(CompareOp::Lt),
+            "<=" => Some(CompareOp::Le),
+            ">" => Some(CompareOp::Gt),
+            ">=" => Some(CompareOp::Ge),
+            _ => None,
+        };
+        if let Some(compare_op) = compare_op {
+            let number = self.parse_number()?;
+            return Ok(FormatExpression::TagCompare(
+                tag_name, compare_op, number,
+            ));
+        }
+
+        match op {
+            "==" | "!=" => {
+                let equals = op == "==";
+                let expression = if let Some(Token::Number(number)) =
+                    self.peek()
+                {
+                    let number = *number;
+                    self.index += 1;
+                    FormatExpression::TagCompare(
+                        tag_name,
+                        CompareOp::Eq,
+                        number,
+                    )
+                } else {
+                    FormatExpression::TagEquals(tag_name, self.parse_word()?)
+                };
+                Ok(negate_unless(equals, expression))
+            }
+            "=~" => {
+                let position = self.position();
+                let regex = FormatRegex::try_from(self.parse_word()?)
+                    .map_err(|e| (position, e.message()))?;
+                Ok(FormatExpression::TagMatches(tag_name, regex))
+            }
+            _ => Err((
+                position,
+                format!(
+                    "unexpected `{}`, expected one of `==`, `!=`, `=~`, `<`, \
+                     `<=`, `>`, `>=`",
+                    op
+                ),
+            )),
+        }
+    }
+}
+
+fn negate_unless(
+    condition: bool,
+    expression: FormatExpression,
+) -> FormatExpression {
+    if condition {
+        expression
+    } else {
+        FormatExpression::Not(Box::new(expression))
+    }
+}
@@ -1,4 +1,8 @@
-use crate::cmus_status::data::CmusPlaybackStatus;
+use std::convert::TryFrom;
+
+use super::{FormatGlob, FormatRegex, FormatRegexNoCase};
+use crate::cmus_status::data::{CmusAaaMode, CmusPlaybackStatus, Seconds};
+use serde::{Deserialize, Deserializer};
 
 #[derive(Debug, Clone, Deserialize)]
 pub enum FormatExpression {
@@ -12,9 +16,114 @@ pub enum FormatExpression {
     Or(Box<FormatExpression>, Box<FormatExpression>),
     /// Inverts the given expression.
     Not(Box<FormatExpression>),
+    /// Returns `true` if all of the given expressions are `true`.
+    All(Vec<FormatExpression>),
+    /// Returns `true` if any of the given expressions are `true`.
+    Any(Vec<FormatExpression>),
+    /// An expression in its textual form, such as
+    /// `status == playing && has(artist)`.
+    /// It is parsed when the config is loaded,
+    /// see `expression_parser` for the syntax.
+    #[serde(deserialize_with = "deserialize_text_expression")]
+    Expr(Box<FormatExpression>),
     /// Returns `true` if the given `CmusPlaybackStatus`
     /// is the currently playing song's `CmusPlaybackStatus`.
     IsStatus(CmusPlaybackStatus),
     /// Returns `true` if the given tag is set for the current track.
     HasTag(String),
+    /// Returns `true` if the given tag's value is exactly the given string.
+    TagEquals(String, String),
+    /// Returns `true` if the given tag's value matches the given regex.
+    TagMatches(String, FormatRegex),
+    /// Returns `true` if the given tag's value matches the given regex,
+    /// ignoring case.
+    TagMatchesNoCase(String, FormatRegexNoCase),
+    /// Compares the given tag's value as a number with the given number.
+    /// Dates compare by their year, and values like `3/12` by their number.
+    /// Returns `false` if the tag doesn't exist or isn't a number.
+    TagCompare(String, CompareOp, f64),
+    /// Returns `true` if the playing file's path matches the given glob.
+    FileMatches(FormatGlob),
+    /// Returns `true` if the playing file has the given extension,
+    /// ignoring case.
+    HasExtension(String),
+    /// Returns `true` if an internet radio stream is playing.
+    IsStream,
+    /// Returns `true` if cmus' `shuffle` setting is enabled.
+    IsShuffle,
+    /// Returns `true` if cmus' `repeat` setting is enabled.
+    IsRepeat,
+    /// Returns `true` if cmus' `repeat_current` setting is enabled.
+    IsRepeatCurrent,
+    /// Returns `true` if cmus' `continue` setting is enabled.
+    IsContinue,
+    /// Returns `true` if cmus' `play_library` setting is enabled.
+    IsPlayLibrary,
+    /// Returns `true` if cmus' `play_sorted` setting is enabled.
+    IsPlaySorted,
+    /// Returns `true` if cmus' `softvol` setting is enabled.
+    IsSoftvol,
+    /// Returns `true` if the given `CmusAaaMode`
+    /// is cmus' current `aaa_mode` setting.
+    AaaMode(CmusAaaMode),
+    /// Returns `true` if cmus reported a playback position,
+    /// which is the case if any track is loaded.
+    HasTime,
+    /// Returns `true` if the playback position is at least
+    /// the given amount of seconds.
+    PositionAtLeast(Seconds),
+    /// Returns `true` if less than the given amount of seconds
+    /// of the playing track remain.
+    /// Returns `false` if the duration is unknown.
+    RemainingLessThan(Seconds),
+    /// Returns `true` if the playing track is longer than
+    /// the given amount of seconds.
+    /// Returns `false` if the duration is unknown.
+    DurationLongerThan(Seconds),
+    /// Returns `true` if the playback percentage (`0.0` to `100.0`)
+    /// is above the given percentage.
+    /// Returns `false` if the duration is unknown.
+    PercentAbove(f32),
+}
+
+fn deserialize_text_expression<'de, D>(
+    deserializer: D,
+) -> Result<Box<FormatExpression>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    FormatExpression::try_from(text.as_str())
+        .map(Box::new)
+        .map_err(|e| serde::de::Error::custom(e.message()))
+}
+
+/// How `FormatExpression::TagCompare` compares the tag with its number.
+#[derive(Debug, Clone, Deserialize)]
+pub enum CompareOp {
+    /// Less than
+    Lt,
+    /// Less than or equal
+    Le,
+    /// Equal
+    Eq,
+    /// Not equal
+    Ne,
+    /// Greater than or equal
+    Ge,
+    /// Greater than
+    Gt,
+}
+
+impl CompareOp {
+    pub fn compare(&self, a: f64, b: f64) -> bool {
+        match self {
+            CompareOp::Lt => a < b,
+            CompareOp::Le => a <= b,
+            CompareOp::Eq => a == b,
+            CompareOp::Ne => a != b,
+            CompareOp::Ge => a >= b,
+            CompareOp::Gt => a > b,
+        }
+    }
 }
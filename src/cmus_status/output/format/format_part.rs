@@ -1,6 +1,7 @@
 use std::convert::TryFrom;
 
-use super::FormatExpression;
+use super::{EscapeTarget, FormatExpression, FormatRegex};
+use super::{StyleConfig, TimeFormat};
 use crate::cmus_status::data::CmusPlaybackStatus;
 use crate::error::prelude::*;
 
@@ -17,6 +18,10 @@ pub enum FormatPart {
     /// Prints the currently playing song's name.
     Title,
 
+    /// Prints the title of the playing internet radio stream.
+    /// If no stream is playing, prints nothing.
+    Stream,
+
     /// Prints the `CmusPlaybackStatus` of the playing song.
     Status,
 
@@ -24,6 +29,98 @@ pub enum FormatPart {
     /// If the tag doesn't exist, prints nothing.
     Tag(String),
 
+    /// Prints the track number from the `tracknumber` tag,
+    /// padded with leading zeros to `pad` digits.
+    /// If the tag doesn't exist or isn't a number, prints nothing.
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ TrackNumber(pad: 2) }. %{ Tag(\"title\") }"
+    /// ```
+    TrackNumber {
+        #[serde(default)]
+        pad: usize,
+    },
+
+    /// Prints the total number of tracks, from the `tracknumber` tag
+    /// (such as `3/12`), or from the `tracktotal` or `totaltracks` tags.
+    /// Padded with leading zeros to `pad` digits.
+    /// If the total is unknown, prints nothing.
+    TrackTotal {
+        #[serde(default)]
+        pad: usize,
+    },
+
+    /// Prints the disc number from the `discnumber` tag,
+    /// padded with leading zeros to `pad` digits.
+    /// If the tag doesn't exist or isn't a number, prints nothing.
+    DiscNumber {
+        #[serde(default)]
+        pad: usize,
+    },
+
+    /// Prints the total number of discs, from the `discnumber` tag
+    /// (such as `1/2`), or from the `disctotal` or `totaldiscs` tags.
+    /// Padded with leading zeros to `pad` digits.
+    /// If the total is unknown, prints nothing.
+    DiscTotal {
+        #[serde(default)]
+        pad: usize,
+    },
+
+    /// Prints the year from the `date` tag (or `originaldate`).
+    /// If the date is missing or can't be parsed, prints nothing.
+    Year,
+
+    /// Prints the full date from the `date` tag (or `originaldate`),
+    /// as `YYYY-MM-DD`, `YYYY-MM` or `YYYY`, depending on what the tag has.
+    /// If the date is missing or can't be parsed, prints nothing.
+    Date,
+
+    /// Prints the playback position of the playing song,
+    /// with the given `TimeFormat`.
+    /// The `TimeFormat` can be `"auto"`, `"human"`, or a pattern
+    /// such as `"%m:%S"` or `"%h:%M:%S"`.
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ Position(\"auto\") } / %{ Duration(\"auto\") }"
+    /// ```
+    Position(TimeFormat),
+
+    /// Prints the duration of the playing song,
+    /// with the given `TimeFormat`.
+    /// If the duration is unknown (for streams), prints nothing.
+    Duration(TimeFormat),
+
+    /// Prints the remaining time of the playing song,
+    /// with the given `TimeFormat`.
+    /// With the `"human"` format, prints something like `3 min left`.
+    /// If the duration is unknown (for streams), prints nothing.
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ Remaining(\"-%m:%S\") }"
+    /// ```
+    Remaining(TimeFormat),
+
+    /// Prints the value of the given cmus setting,
+    /// as it is printed by `cmus-remote -Q`.
+    /// If the setting doesn't exist, prints nothing.
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ Setting(\"shuffle\") }"
+    /// ```
+    Setting(String),
+
+    /// Prints the raw value of an unknown line from `cmus-remote -Q`,
+    /// where the given string is the line's first word.
+    /// Only available with the `Lenient` parse mode.
+    /// If no such line exists, prints nothing.
+    /// __Config example:__
+    /// ```toml
+    ///     parse_mode = "Lenient"
+    ///     format = "%{ Raw(\"lyrics\") }"
+    /// ```
+    Raw(String),
+
     /// TODO: Deprecated, use with `FormatPart::If` conditional.
     /// If the first argument's status is the current `CmusPlaybackStatus`,
     /// then, print the given string.
@@ -33,18 +130,139 @@ pub enum FormatPart {
     ///   - Stopped
     MatchStatus(CmusPlaybackStatus, String),
 
-    /// Truncate the given `FormatPart` to the given length (`usize`).
-    /// Max length is inclusive.
+    /// Truncate the given `FormatPart` to the given display width (`usize`),
+    /// with a trailing `...`.
+    /// Max width is inclusive.
     /// __Config example:__
     /// ```toml
     ///     format = "%{ Truncate(Status, 60) }"
     /// ```
     Truncate(Box<FormatPart>, usize), // Inclusive
 
+    /// Truncate the given `FormatPart` to the given display `width`,
+    /// replacing the removed text with the `ellipsis` (default `...`).
+    /// The `side` (default `End`) from which text is removed can be one of:
+    ///   - Start
+    ///   - Middle
+    ///   - End
+    ///
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ TruncateWith(part: Title, width: 20, ellipsis: \"…\", side: Middle) }"
+    /// ```
+    TruncateWith {
+        part:     Box<FormatPart>,
+        width:    usize,
+        #[serde(default = "default_ellipsis")]
+        ellipsis: String,
+        #[serde(default)]
+        side:     TruncateSide,
+    },
+
+    /// Pad the given `FormatPart` to at least the given display width,
+    /// with the given `Alignment` and fill character.
+    /// The `Alignment` can be one of:
+    ///   - Left
+    ///   - Right
+    ///   - Center
+    ///
+    /// If the `FormatPart` prints nothing, prints nothing.
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ Pad(Volume(Average), 3, Right, ' ') }%"
+    /// ```
+    Pad(Box<FormatPart>, usize, Alignment, char),
+
+    /// Pad and truncate the given `FormatPart` to exactly the given
+    /// display `width`, so the rest of the status line doesn't move around.
+    /// Uses the `align` (default `Left`) and `fill` character (default ` `)
+    /// like `FormatPart::Pad`, and the `ellipsis` (default `...`) and
+    /// `side` (default `End`) like `FormatPart::TruncateWith`.
+    /// If the `FormatPart` prints nothing, prints only the fill characters.
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ FixedWidth(part: Title, width: 30, ellipsis: \"…\") }"
+    /// ```
+    FixedWidth {
+        part:     Box<FormatPart>,
+        width:    usize,
+        #[serde(default)]
+        align:    Alignment,
+        #[serde(default = "default_fill")]
+        fill:     char,
+        #[serde(default = "default_ellipsis")]
+        ellipsis: String,
+        #[serde(default)]
+        side:     TruncateSide,
+    },
+
     /// Run `htmlescape::encode_minimal` on the wrapped
     /// `FormatPart`'s resulting string.
     HtmlEscape(Box<FormatPart>),
 
+    /// Escapes the wrapped `FormatPart`'s resulting string
+    /// for the given `EscapeTarget`, which can be one of:
+    ///   - Html
+    ///   - Pango
+    ///   - Json
+    ///   - Shell
+    ///   - Tmux
+    ///   - Lemonbar
+    ///   - Polybar
+    ///
+    /// The wrapped `FormatPart` isn't escaped with the config's
+    /// default `escape` as well.
+    /// __Config example:__
+    /// ```toml
+    ///     format = '''
+    ///     %{ Escape(Title, Tmux) }
+    ///     '''
+    /// ```
+    Escape(Box<FormatPart>, EscapeTarget),
+
+    /// Styles the wrapped `FormatPart`'s resulting string with colors,
+    /// bold, italic or underline, rendered for the config's `style_target`.
+    /// Without a `style_target`, the string is returned unstyled.
+    /// Colors can be basic color names, hex colors,
+    /// or the names of colors in the config's `theme`.
+    /// __Config example:__
+    /// ```toml
+    ///     format = '''
+    ///     %{ Style(Title, (fg: "accent", bold: true)) }
+    ///     '''
+    ///     style_target = "Tmux"
+    ///     [theme]
+    ///     accent = "#ff8800"
+    /// ```
+    Style(Box<FormatPart>, StyleConfig),
+
+    /// Converts the wrapped `FormatPart`'s resulting string to uppercase.
+    Upper(Box<FormatPart>),
+
+    /// Converts the wrapped `FormatPart`'s resulting string to lowercase.
+    Lower(Box<FormatPart>),
+
+    /// Uppercases the first letter of each word
+    /// of the wrapped `FormatPart`'s resulting string,
+    /// and lowercases the rest.
+    TitleCase(Box<FormatPart>),
+
+    /// Removes leading and trailing whitespace
+    /// from the wrapped `FormatPart`'s resulting string.
+    Trim(Box<FormatPart>),
+
+    /// Replaces all matches of the regex in the wrapped `FormatPart`'s
+    /// resulting string with the replacement string, which can refer to
+    /// capture groups with `$1` or `${name}`.
+    /// __Config example:__
+    /// ```toml
+    ///     format = '''
+    ///     %{ Replace(Title, " \(Remastered \d+\)$", "") }
+    ///     %{ Replace(Tag("artist"), "(.+) feat\. (.+)", "$1 ft. $2") }
+    ///     '''
+    /// ```
+    Replace(Box<FormatPart>, FormatRegex, String),
+
     /// Prints a ProgressBar with the given `ProgressBarConfig`.
     /// `ProgressBarConfig` can be a string such as:
     /// __Config example:__
@@ -59,8 +277,82 @@ pub enum FormatPart {
     /// The "full" characters are printed if the playback percentage of the track has reached that
     /// point, the "empty" characters if it hasn't.
     /// The total length of the string is also the printed length.
+    ///
+    /// `ProgressBarConfig` can also be a struct, with an explicit `width`
+    /// (including `start` and `end`), multi-character `start` and `end` caps,
+    /// and an optional `head` character, printed after the "full" characters.
+    /// All fields except `width` are optional.
+    /// __Config example:__
+    /// ```toml
+    ///     format = """
+    ///     %{ ProgressBar((width: 12, start: "[", end: "]",
+    ///        full: "=", empty: " ", head: ">")) }
+    ///     """
+    /// ```
+    /// ... which prints something like `[=====>    ]`.
     ProgressBar(ProgressBarConfig),
 
+    /// Prints a ProgressBar like `FormatPart::ProgressBar`,
+    /// but fills the partially reached character with a glyph
+    /// from the `ramp`, for sub-character precision.
+    /// The `ramp`'s glyphs are for `1/n`, `2/n`, ..., `n/n` of a filled
+    /// character, defaulting to the Unicode eighth blocks `▏▎▍▌▋▊▉█`.
+    /// __Config example:__
+    /// ```toml
+    ///     format = """
+    ///     %{ SmoothProgressBar(bar: "[█ ]") }
+    ///     %{ SmoothProgressBar(bar: "[#  ]", ramp: ".:") }
+    ///     """
+    /// ```
+    SmoothProgressBar {
+        bar:  ProgressBarConfig,
+        #[serde(default)]
+        ramp: ProgressBarRamp,
+    },
+
+    /// Prints the playback percentage (`0` to `100`) of the playing song,
+    /// with the given number of `decimals` (default `0`, at most `6`),
+    /// followed by the `suffix` (default `%`).
+    /// The `rounding` (default `Round`) can be one of:
+    ///   - Round
+    ///   - Floor
+    ///   - Ceil
+    ///
+    /// If the duration is unknown or zero, prints nothing.
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ Percent(decimals: 1, rounding: Floor) }"
+    /// ```
+    Percent {
+        #[serde(default)]
+        decimals: usize,
+        #[serde(default = "default_percent_suffix")]
+        suffix:   String,
+        #[serde(default)]
+        rounding: Rounding,
+    },
+
+    /// Prints the volume percentage (`0` to `100`) of the given `VolumeChannel`.
+    /// If cmus didn't report its volume, prints nothing.
+    /// The `VolumeChannel` can be one of:
+    ///   - Average
+    ///   - Left
+    ///   - Right
+    ///
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ Volume(Average) }%"
+    /// ```
+    Volume(VolumeChannel),
+
+    /// Prints the volume of the given `VolumeChannel` as a bar.
+    /// Uses the same `ProgressBarConfig` syntax as `FormatPart::ProgressBar`.
+    /// __Config example:__
+    /// ```toml
+    ///     format = "%{ VolumeBar(Average, \"[||||    ]\") }"
+    /// ```
+    VolumeBar(VolumeChannel, ProgressBarConfig),
+
     /// A list of `FormatPart`s.
     /// Useful with `FormatPart::If`.
     /// __Config example:__
@@ -114,40 +406,162 @@ impl<'a> From<Box<&'a FormatPart>> for &'a FormatPart {
 }
 
 #[derive(Debug, Clone, Deserialize)]
-#[serde(try_from = "String")]
+#[serde(try_from = "ProgressBarConfigRepr")]
 pub struct ProgressBarConfig {
-    pub start: Option<char>,
-    pub end: Option<char>,
-    pub full: char,
-    pub empty: char,
+    pub start:   String,
+    pub end:     String,
+    pub full:    char,
+    pub empty:   char,
+    pub head:    Option<char>,
     total_width: usize,
 }
 
 impl ProgressBarConfig {
     pub fn inner_width(&self) -> usize {
         self.total_width
-            - if self.start.is_some() { 1 } else { 0 }
-            - if self.end.is_some() { 1 } else { 0 }
+            .saturating_sub(self.start.chars().count())
+            .saturating_sub(self.end.chars().count())
     }
 
-    pub fn text_with_filled(&self, filled_characters: usize) -> String {
-        assert!(self.total_width > filled_characters);
+    /// Returns the bar filled up to the given percentage (`0.0` to `1.0`).
+    pub fn text_with_percentage(&self, percentage: f32) -> String {
+        let characters = (self.inner_width() as f32
+            * percentage.clamp(0.0, 1.0))
+        .round() as usize;
+        self.text_with_filled(characters)
+    }
 
-        let mut s = String::new();
-        if let Some(start) = self.start {
-            s.push(start);
+    /// Returns the bar filled up to the given percentage (`0.0` to `1.0`),
+    /// where the partially reached character is a glyph from the `ramp`.
+    /// The `head` isn't used for smooth bars.
+    pub fn text_with_percentage_smooth(
+        &self,
+        percentage: f32,
+        ramp: &ProgressBarRamp,
+    ) -> String {
+        let inner_width = self.inner_width();
+        let filled = inner_width as f32 * percentage.clamp(0.0, 1.0);
+        let filled_characters = filled.floor() as usize;
+        if filled_characters >= inner_width {
+            return self
+                .text_with_inner(self.full.to_string().repeat(inner_width));
         }
-        s.push_str(self.full.to_string().repeat(filled_characters).as_str());
-        s.push_str(
+
+        let steps = ramp.0.len();
+        let step = ((filled - filled_characters as f32) * steps as f32).round()
+            as usize;
+        let partial = if step == 0 {
+            self.empty
+        } else {
+            ramp.0[step - 1]
+        };
+
+        let mut inner = self.full.to_string().repeat(filled_characters);
+        inner.push(partial);
+        inner.push_str(
             self.empty
                 .to_string()
-                .repeat(self.inner_width() - filled_characters)
+                .repeat(inner_width - filled_characters - 1)
                 .as_str(),
         );
-        if let Some(end) = self.end {
-            s.push(end);
+        self.text_with_inner(inner)
+    }
+
+    /// Returns the bar with the given number of filled characters,
+    /// which is capped at the `inner_width`.
+    /// If the bar isn't completely filled, the `head` character
+    /// is printed after the filled characters.
+    pub fn text_with_filled(&self, filled_characters: usize) -> String {
+        let inner_width = self.inner_width();
+        let filled_characters = filled_characters.min(inner_width);
+
+        let mut inner = self.full.to_string().repeat(filled_characters);
+        let mut empty_characters = inner_width - filled_characters;
+        if let Some(head) = self.head.filter(|_| empty_characters > 0) {
+            inner.push(head);
+            empty_characters -= 1;
+        }
+        inner
+            .push_str(self.empty.to_string().repeat(empty_characters).as_str());
+        self.text_with_inner(inner)
+    }
+
+    fn text_with_inner(&self, inner: String) -> String {
+        format!("{}{}{}", self.start, inner, self.end)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ProgressBarConfigRepr {
+    String(String),
+    Struct {
+        width: usize,
+        #[serde(default)]
+        start: String,
+        #[serde(default)]
+        end:   String,
+        #[serde(default = "default_progress_bar_full")]
+        full:  String,
+        #[serde(default = "default_progress_bar_empty")]
+        empty: String,
+        #[serde(default)]
+        head:  Option<String>,
+    },
+}
+
+fn default_progress_bar_full() -> String {
+    "#".to_string()
+}
+
+fn default_progress_bar_empty() -> String {
+    " ".to_string()
+}
+
+impl TryFrom<ProgressBarConfigRepr> for ProgressBarConfig {
+    type Error = Error;
+    fn try_from(repr: ProgressBarConfigRepr) -> MyResult<Self> {
+        match repr {
+            ProgressBarConfigRepr::String(s) => ProgressBarConfig::try_from(s),
+            ProgressBarConfigRepr::Struct {
+                width,
+                start,
+                end,
+                full,
+                empty,
+                head,
+            } => {
+                let single_char = |name: &str, s: String| {
+                    let mut chars = s.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => Ok(c),
+                        _ => Err(Error::ProgressBarConfigInvalid(format!(
+                            "`{}` must be a single character: {:?}",
+                            name, s
+                        ))),
+                    }
+                };
+                let config = ProgressBarConfig {
+                    full: single_char("full", full)?,
+                    empty: single_char("empty", empty)?,
+                    head: head
+                        .map(|head| single_char("head", head))
+                        .transpose()?,
+                    start,
+                    end,
+                    total_width: width,
+                };
+                if config.inner_width() == 0 {
+                    Err(Error::ProgressBarConfigInvalid(format!(
+                        "`width` must be larger than the `start` and `end` \
+                         characters: {}",
+                        width
+                    )))
+                } else {
+                    Ok(config)
+                }
+            }
         }
-        s
     }
 }
 
@@ -160,28 +574,115 @@ impl TryFrom<String> for ProgressBarConfig {
             Err(Error::ProgressBarConfigMinLen(2, s))
         } else if len == 2 {
             Ok(ProgressBarConfig {
-                start: None,
-                end: None,
-                full: *chars.get(0).unwrap(),
-                empty: *chars.get(1).unwrap(),
+                start:       String::new(),
+                end:         String::new(),
+                full:        *chars.first().unwrap(),
+                empty:       *chars.get(1).unwrap(),
+                head:        None,
                 total_width: len,
             })
         } else if len == 3 {
             Ok(ProgressBarConfig {
-                start: Some(*chars.get(0).unwrap()),
-                end: None,
-                full: *chars.get(1).unwrap(),
-                empty: *chars.get(2).unwrap(),
+                start:       chars.first().unwrap().to_string(),
+                end:         String::new(),
+                full:        *chars.get(1).unwrap(),
+                empty:       *chars.get(2).unwrap(),
+                head:        None,
                 total_width: len,
             })
         } else {
             Ok(ProgressBarConfig {
-                start: Some(*chars.get(0).unwrap()),
-                end: Some(*chars.get(len - 1).unwrap()),
-                full: *chars.get(1).unwrap(),
-                empty: *chars.get(len - 2).unwrap(),
+                start:       chars.first().unwrap().to_string(),
+                end:         chars.get(len - 1).unwrap().to_string(),
+                full:        *chars.get(1).unwrap(),
+                empty:       *chars.get(len - 2).unwrap(),
+                head:        None,
                 total_width: len,
             })
         }
     }
 }
+
+/// The glyphs for partially filled characters of a `SmoothProgressBar`,
+/// from the least to the most filled.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ProgressBarRamp(Vec<char>);
+
+impl Default for ProgressBarRamp {
+    fn default() -> Self {
+        Self("▏▎▍▌▋▊▉█".chars().collect())
+    }
+}
+
+impl TryFrom<String> for ProgressBarRamp {
+    type Error = Error;
+    fn try_from(s: String) -> MyResult<Self> {
+        if s.is_empty() {
+            Err(Error::ProgressBarRampEmpty)
+        } else {
+            Ok(Self(s.chars().collect()))
+        }
+    }
+}
+
+fn default_fill() -> char {
+    ' '
+}
+
+fn default_ellipsis() -> String {
+    "...".to_string()
+}
+
+fn default_percent_suffix() -> String {
+    "%".to_string()
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub enum Rounding {
+    #[default]
+    Round,
+    Floor,
+    Ceil,
+}
+
+impl Rounding {
+    pub fn round(&self, n: f32) -> f32 {
+        match self {
+            Rounding::Round => n.round(),
+            Rounding::Floor => n.floor(),
+            Rounding::Ceil => n.ceil(),
+        }
+    }
+}
+
+/// Where text is placed, when it is padded.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub enum Alignment {
+    /// Padding is added to the right, `text   `.
+    #[default]
+    Left,
+    /// Padding is added to the left, `   text`.
+    Right,
+    /// Padding is added to both sides, ` text  `.
+    Center,
+}
+
+/// The side from which text is removed, when it is truncated.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub enum TruncateSide {
+    /// Removes the start of the text, `...text`.
+    Start,
+    /// Removes the middle of the text, `te...xt`.
+    Middle,
+    /// Removes the end of the text, `text...`.
+    #[default]
+    End,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum VolumeChannel {
+    Average,
+    Left,
+    Right,
+}
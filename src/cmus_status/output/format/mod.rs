@@ -1,9 +1,20 @@
+mod escape;
+mod expression_parser;
 mod format_expression;
 mod format_part;
+mod pattern;
+mod style;
+mod time_format;
 
 pub mod prelude {
-    pub use super::format_expression::FormatExpression;
-    pub use super::format_part::FormatPart;
+    pub use super::escape::EscapeTarget;
+    pub use super::format_expression::{CompareOp, FormatExpression};
+    pub use super::format_part::{Alignment, TruncateSide};
+    pub use super::format_part::{FormatPart, Rounding, VolumeChannel};
+    pub use super::format_part::{ProgressBarConfig, ProgressBarRamp};
+    pub use super::pattern::{FormatGlob, FormatRegex, FormatRegexNoCase};
+    pub use super::style::{Color, StyleConfig, StyleTarget, Theme};
+    pub use super::time_format::{TimeFormat, TimePatternPart};
     pub use super::Format;
 }
 
@@ -22,22 +33,25 @@ pub struct Format {
 }
 
 impl Format {
-    pub fn iter(&self) -> std::slice::Iter<FormatPart> {
+    pub fn iter(&self) -> std::slice::Iter<'_, FormatPart> {
         self.parts.iter()
     }
 
     fn try_from_string(string: String) -> MyResult<Self> {
-        let re =
-            Regex::new(r"(%\{\s*(?P<keyword>(.|\s)+?)\s*\})|(?P<text>.+?)")
-                .unwrap();
+        // Quoted strings and chars in keywords may contain `}` or `"`,
+        // such as regexes or the fill character of `Pad`.
+        let re = Regex::new(
+            r#"(%\{\s*(?P<keyword>("([^"\\]|\\.)*"|'([^'\\]|\\.)'|[^"}])+?)\s*\})|(?P<text>.+?)"#,
+        )
+        .unwrap();
         let mut parts = Vec::new();
 
         for caps in re.captures_iter(string.as_str()) {
             if let Some(keyword) = caps.name("keyword") {
-                let keyword = keyword.as_str();
-                let part =
-                    ron::de::from_str::<FormatPart>(keyword).or_else(|e| {
-                        Err(Error::FailedParsingConfig(None, format!("{}", e)))
+                let keyword = expand_text_expressions(keyword.as_str());
+                let part = ron::de::from_str::<FormatPart>(keyword.as_ref())
+                    .map_err(|e| {
+                        Error::FailedParsingConfig(None, format!("{}", e))
                     })?;
                 parts.push(part);
             }
@@ -60,6 +74,15 @@ impl Format {
     }
 }
 
+/// Expands the shorthand `If("status == playing", ...)`
+/// to `If(Expr("status == playing"), ...)`, same for `IfElse`.
+fn expand_text_expressions(keyword: &str) -> std::borrow::Cow<'_, str> {
+    let re =
+        Regex::new(r#"\b(?P<part>If|IfElse)\(\s*(?P<text>"([^"\\]|\\.)*")"#)
+            .unwrap();
+    re.replace_all(keyword, "$part(Expr($text)")
+}
+
 impl TryFrom<&str> for Format {
     type Error = Error;
     fn try_from(string: &str) -> MyResult<Self> {
@@ -0,0 +1,90 @@
+//! Patterns for `FormatExpression`s, which are compiled once
+//! when the config is loaded.
+
+use std::convert::TryFrom;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::error::prelude::*;
+use regex::{Regex, RegexBuilder};
+
+/// A regular expression, see the `regex` crate for its syntax.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct FormatRegex(Regex);
+
+impl FormatRegex {
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+
+    /// Returns the byte range of each match, with its `replacement`,
+    /// which may refer to capture groups with `$1` or `$name`.
+    pub fn replacements(
+        &self,
+        text: &str,
+        replacement: &str,
+    ) -> Vec<(Range<usize>, String)> {
+        self.0
+            .captures_iter(text)
+            .map(|caps| {
+                let mut replaced = String::new();
+                caps.expand(replacement, &mut replaced);
+                (caps.get(0).unwrap().range(), replaced)
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<String> for FormatRegex {
+    type Error = Error;
+    fn try_from(s: String) -> MyResult<Self> {
+        build_regex(s.as_str(), false).map(Self)
+    }
+}
+
+/// A case-insensitive regular expression.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct FormatRegexNoCase(Regex);
+
+impl FormatRegexNoCase {
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl TryFrom<String> for FormatRegexNoCase {
+    type Error = Error;
+    fn try_from(s: String) -> MyResult<Self> {
+        build_regex(s.as_str(), true).map(Self)
+    }
+}
+
+fn build_regex(s: &str, case_insensitive: bool) -> MyResult<Regex> {
+    RegexBuilder::new(s)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map_err(|e| Error::InvalidRegex(s.to_string(), e.to_string()))
+}
+
+/// A glob pattern such as `*.flac` or `/home/*/Music/**/*.mp3`,
+/// see the `glob` crate for its syntax.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct FormatGlob(glob::Pattern);
+
+impl FormatGlob {
+    pub fn matches_path(&self, path: &Path) -> bool {
+        self.0.matches_path(path)
+    }
+}
+
+impl TryFrom<String> for FormatGlob {
+    type Error = Error;
+    fn try_from(s: String) -> MyResult<Self> {
+        glob::Pattern::new(s.as_str())
+            .map(Self)
+            .map_err(|e| Error::InvalidGlob(s, e.to_string()))
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::error::prelude::*;
+
+const COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// The xterm defaults for the 16 basic colors,
+/// used by targets which only understand hex colors.
+const COLOR_HEX: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00),
+    (0xcd, 0x00, 0x00),
+    (0x00, 0xcd, 0x00),
+    (0xcd, 0xcd, 0x00),
+    (0x00, 0x00, 0xee),
+    (0xcd, 0x00, 0xcd),
+    (0x00, 0xcd, 0xcd),
+    (0xe5, 0xe5, 0xe5),
+    (0x7f, 0x7f, 0x7f),
+    (0xff, 0x00, 0x00),
+    (0x00, 0xff, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x5c, 0x5c, 0xff),
+    (0xff, 0x00, 0xff),
+    (0x00, 0xff, 0xff),
+    (0xff, 0xff, 0xff),
+];
+
+/// Named colors, configured with the `theme` config key.
+pub type Theme = HashMap<String, Color>;
+
+/// What the `Style` `FormatPart` renders styles for,
+/// configured with the `style_target` config key.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum StyleTarget {
+    /// ANSI escape codes, for terminals.
+    Ansi,
+    /// `#[fg=red,bold]` styles, for tmux' status line.
+    Tmux,
+    /// `<span>` markup, for Waybar and other Pango based bars.
+    Pango,
+    /// `%{F#ff0000}` formatting tags, no bold or italic.
+    Lemonbar,
+    /// `%{F#ff0000}` formatting tags, no bold or italic.
+    Polybar,
+    /// `${color #ff0000}` variables, only the foreground color.
+    Conky,
+}
+
+/// The style of the `Style` `FormatPart`.
+/// All fields are optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StyleConfig {
+    #[serde(default)]
+    pub fg:        Color,
+    #[serde(default)]
+    pub bg:        Color,
+    #[serde(default)]
+    pub bold:      bool,
+    #[serde(default)]
+    pub italic:    bool,
+    #[serde(default)]
+    pub underline: bool,
+}
+
+/// A color, deserialized from a string, which can be one of:
+///   - `""` or `"default"`, which doesn't change the color
+///   - a basic color name, such as `"red"`, or `"bright-red"`
+///   - a hex color, such as `"#ff8800"`
+///   - the name of a color in the config's `theme`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Color {
+    #[default]
+    Default,
+    /// One of the 16 basic colors, the bright ones are `8..16`.
+    Basic(u8),
+    Rgb(u8, u8, u8),
+    Theme(String),
+}
+
+impl Color {
+    /// Returns the color, or the theme's color with that name.
+    /// Returns `None` for the default color, and unknown theme colors.
+    fn resolve<'a>(&'a self, theme: &'a Theme) -> Option<&'a Color> {
+        match self {
+            Color::Default => None,
+            Color::Theme(name) => match theme.get(name) {
+                Some(Color::Theme(_)) | None => None,
+                Some(color) => color.resolve(theme),
+            },
+            color => Some(color),
+        }
+    }
+
+    /// Returns the color as `#rrggbb`, resolving theme colors.
+    /// Returns `None` for the default color, and unknown theme colors.
+    pub fn to_hex(&self, theme: &Theme) -> Option<String> {
+        self.resolve(theme).map(Color::hex)
+    }
+
+    fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            Color::Basic(index) => COLOR_HEX[*index as usize],
+            Color::Rgb(r, g, b) => (*r, *g, *b),
+            Color::Default | Color::Theme(_) => (0, 0, 0),
+        }
+    }
+
+    fn hex(&self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    fn ansi(&self, background: bool) -> String {
+        let offset = if background { 10 } else { 0 };
+        match self {
+            Color::Basic(index) if *index < 8 => {
+                (30 + offset + index).to_string()
+            }
+            Color::Basic(index) => (90 + offset + index - 8).to_string(),
+            _ => {
+                let (r, g, b) = self.rgb();
+                format!("{};2;{};{};{}", 38 + offset, r, g, b)
+            }
+        }
+    }
+
+    fn tmux(&self) -> String {
+        match self {
+            Color::Basic(index) if *index < 8 => {
+                COLOR_NAMES[*index as usize].to_string()
+            }
+            Color::Basic(index) => {
+                format!("bright{}", COLOR_NAMES[*index as usize - 8])
+            }
+            _ => self.hex(),
+        }
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = Error;
+
+    fn try_from(s: String) -> MyResult<Self> {
+        let name = s.trim().to_lowercase();
+        if name.is_empty() || name == "default" {
+            return Ok(Color::Default);
+        }
+
+        if let Some(hex) = name.strip_prefix('#') {
+            let channel = |i: usize| {
+                hex.get(i..i + 2)
+                    .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+            };
+            return match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok(Color::Rgb(r, g, b)),
+                _ => Err(Error::InvalidColor(s)),
+            };
+        }
+
+        let (bright, base) = match name.strip_prefix("bright") {
+            Some(base) => (true, base.trim_start_matches('-')),
+            None => (false, name.as_str()),
+        };
+        match COLOR_NAMES.iter().position(|color| *color == base) {
+            Some(index) => {
+                Ok(Color::Basic(index as u8 + if bright { 8 } else { 0 }))
+            }
+            None => Ok(Color::Theme(s.trim().to_string())),
+        }
+    }
+}
+
+impl StyleTarget {
+    /// Returns the target's markup for the given style,
+    /// which goes before and after the styled text.
+    /// Parts of the style, which the target doesn't support, are ignored.
+    pub fn markup(
+        &self,
+        style: &StyleConfig,
+        theme: &Theme,
+    ) -> (String, String) {
+        let fg = style.fg.resolve(theme);
+        let bg = style.bg.resolve(theme);
+
+        match self {
+            StyleTarget::Ansi => {
+                let mut codes = Vec::new();
+                if style.bold {
+                    codes.push("1".to_string());
+                }
+                if style.italic {
+                    codes.push("3".to_string());
+                }
+                if style.underline {
+                    codes.push("4".to_string());
+                }
+                codes.extend(fg.map(|color| color.ansi(false)));
+                codes.extend(bg.map(|color| color.ansi(true)));
+                if codes.is_empty() {
+                    (String::new(), String::new())
+                } else {
+                    (
+                        format!("\x1b[{}m", codes.join(";")),
+                        "\x1b[0m".to_string(),
+                    )
+                }
+            }
+
+            StyleTarget::Tmux => {
+                let mut styles = Vec::new();
+                styles.extend(fg.map(|color| format!("fg={}", color.tmux())));
+                styles.extend(bg.map(|color| format!("bg={}", color.tmux())));
+                if style.bold {
+                    styles.push("bold".to_string());
+                }
+                if style.italic {
+                    styles.push("italics".to_string());
+                }
+                if style.underline {
+                    styles.push("underscore".to_string());
+                }
+                if styles.is_empty() {
+                    (String::new(), String::new())
+                } else {
+                    (
+                        format!("#[{}]", styles.join(",")),
+                        "#[default]".to_string(),
+                    )
+                }
+            }
+
+            StyleTarget::Pango => {
+                let mut attributes = String::new();
+                if let Some(color) = fg {
+                    attributes += &format!(" foreground=\"{}\"", color.hex());
+                }
+                if let Some(color) = bg {
+                    attributes += &format!(" background=\"{}\"", color.hex());
+                }
+                if style.bold {
+                    attributes += " weight=\"bold\"";
+                }
+                if style.italic {
+                    attributes += " style=\"italic\"";
+                }
+                if style.underline {
+                    attributes += " underline=\"single\"";
+                }
+                if attributes.is_empty() {
+                    (String::new(), String::new())
+                } else {
+                    (format!("<span{}>", attributes), "</span>".to_string())
+                }
+            }
+
+            StyleTarget::Lemonbar | StyleTarget::Polybar => {
+                let mut start = String::new();
+                let mut end = String::new();
+                if let Some(color) = fg {
+                    start += &format!("%{{F{}}}", color.hex());
+                    end.insert_str(0, "%{F-}");
+                }
+                if let Some(color) = bg {
+                    start += &format!("%{{B{}}}", color.hex());
+                    end.insert_str(0, "%{B-}");
+                }
+                if style.underline {
+                    start += "%{+u}";
+                    end.insert_str(0, "%{-u}");
+                }
+                (start, end)
+            }
+
+            StyleTarget::Conky => match fg {
+                Some(color) => (
+                    format!("${{color {}}}", color.hex()),
+                    "${color}".to_string(),
+                ),
+                None => (String::new(), String::new()),
+            },
+        }
+    }
+}
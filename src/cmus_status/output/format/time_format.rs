@@ -0,0 +1,169 @@
+use std::convert::TryFrom;
+
+use crate::cmus_status::data::Seconds;
+use crate::error::prelude::*;
+
+const AUTO: &str = "auto";
+const HUMAN: &str = "human";
+const AUTO_PATTERN_SHORT: &str = "%m:%S";
+const AUTO_PATTERN_LONG: &str = "%h:%M:%S";
+
+/// How to print a time, used by the time `FormatPart`s.
+/// Can be one of the following strings:
+///   - `"auto"`
+///     Prints `1:23`, or `1:01:23` if the time is at least an hour.
+///   - `"human"`
+///     Prints human readable times, such as `42 sec`, `3 min` or `1 h 5 min`.
+///   - a pattern, such as `"%m:%S"` or `"%h:%M:%S"`
+///     Lowercase placeholders are printed as they are,
+///     uppercase placeholders are padded to two digits with zeros:
+///       - `%h`, `%H` hours
+///       - `%m`, `%M` minutes
+///       - `%s`, `%S` seconds
+///       - `%%` a literal `%`
+///
+///     The largest unit in the pattern holds the whole time,
+///     so `"%m:%S"` prints `83:20` for 1 hour, 23 minutes and 20 seconds.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub enum TimeFormat {
+    Auto,
+    Human,
+    Pattern(Vec<TimePatternPart>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimePatternPart {
+    Text(String),
+    Hours { padded: bool },
+    Minutes { padded: bool },
+    Seconds { padded: bool },
+}
+
+impl TimeFormat {
+    pub fn is_human(&self) -> bool {
+        matches!(self, TimeFormat::Human)
+    }
+
+    pub fn format(&self, time: Seconds) -> String {
+        match self {
+            TimeFormat::Auto => {
+                let pattern = if time >= 60 * 60 {
+                    AUTO_PATTERN_LONG
+                } else {
+                    AUTO_PATTERN_SHORT
+                };
+                // The auto patterns are always valid.
+                TimeFormat::try_from(pattern).unwrap().format(time)
+            }
+            TimeFormat::Human => format_human(time),
+            TimeFormat::Pattern(parts) => format_pattern(parts, time),
+        }
+    }
+}
+
+fn format_human(time: Seconds) -> String {
+    let hours = time / (60 * 60);
+    let minutes = time / 60 % 60;
+    let seconds = time % 60;
+
+    if hours > 0 {
+        if minutes > 0 {
+            format!("{} h {} min", hours, minutes)
+        } else {
+            format!("{} h", hours)
+        }
+    } else if minutes > 0 {
+        format!("{} min", minutes)
+    } else {
+        format!("{} sec", seconds)
+    }
+}
+
+fn format_pattern(parts: &[TimePatternPart], time: Seconds) -> String {
+    let has_hours = parts
+        .iter()
+        .any(|part| matches!(part, TimePatternPart::Hours { .. }));
+    let has_minutes = parts
+        .iter()
+        .any(|part| matches!(part, TimePatternPart::Minutes { .. }));
+
+    let hours = time / (60 * 60);
+    let minutes = if has_hours { time / 60 % 60 } else { time / 60 };
+    let seconds = if has_hours || has_minutes {
+        time % 60
+    } else {
+        time
+    };
+
+    let number = |number: Seconds, padded: bool| {
+        if padded {
+            format!("{:02}", number)
+        } else {
+            number.to_string()
+        }
+    };
+
+    parts
+        .iter()
+        .map(|part| match part {
+            TimePatternPart::Text(text) => text.to_string(),
+            TimePatternPart::Hours { padded } => number(hours, *padded),
+            TimePatternPart::Minutes { padded } => number(minutes, *padded),
+            TimePatternPart::Seconds { padded } => number(seconds, *padded),
+        })
+        .collect()
+}
+
+impl TryFrom<&str> for TimeFormat {
+    type Error = Error;
+
+    fn try_from(s: &str) -> MyResult<Self> {
+        match s {
+            AUTO => return Ok(TimeFormat::Auto),
+            HUMAN => return Ok(TimeFormat::Human),
+            _ => (),
+        }
+
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                text.push(c);
+                continue;
+            }
+            let part = match chars.next() {
+                Some('%') => {
+                    text.push('%');
+                    continue;
+                }
+                Some('h') => TimePatternPart::Hours { padded: false },
+                Some('H') => TimePatternPart::Hours { padded: true },
+                Some('m') => TimePatternPart::Minutes { padded: false },
+                Some('M') => TimePatternPart::Minutes { padded: true },
+                Some('s') => TimePatternPart::Seconds { padded: false },
+                Some('S') => TimePatternPart::Seconds { padded: true },
+                _ => return Err(Error::InvalidTimeFormat(s.to_string())),
+            };
+            if !text.is_empty() {
+                parts.push(TimePatternPart::Text(text.split_off(0)));
+            }
+            parts.push(part);
+        }
+        if !text.is_empty() {
+            parts.push(TimePatternPart::Text(text));
+        }
+
+        Ok(TimeFormat::Pattern(parts))
+    }
+}
+
+impl TryFrom<String> for TimeFormat {
+    type Error = Error;
+
+    fn try_from(s: String) -> MyResult<Self> {
+        TimeFormat::try_from(s.as_str())
+    }
+}
@@ -1,5 +1,7 @@
 mod builder;
 pub mod format;
+mod rendered;
+pub mod text;
 
 pub use format::prelude::*;
 
@@ -8,12 +10,18 @@ use crate::error::prelude::*;
 use std::fmt;
 
 use builder::StatusOutputBuilder;
+use rendered::RenderedText;
 
 const OVERFLOW_STR: &str = "...";
+/// More `Percent` decimals would overflow the rounding factor.
+const MAX_PERCENT_DECIMALS: usize = 6;
 
 pub struct StatusOutput {
-    data: CmusData,
+    data:   CmusData,
     format: Format,
+    escape: Option<EscapeTarget>,
+    style:  Option<StyleTarget>,
+    theme:  Theme,
 }
 
 impl StatusOutput {
@@ -21,69 +29,79 @@ impl StatusOutput {
         StatusOutputBuilder::default()
     }
 
-    fn get_format_text_for_parts<'a>(
+    fn get_format_text_for_parts(
         &self,
-        parts: Vec<&'a FormatPart>,
-    ) -> String {
-        parts
-            .iter()
-            .filter_map(|part| self.get_format_text(part))
-            .collect::<Vec<String>>()
-            .join("")
+        parts: Vec<&FormatPart>,
+    ) -> RenderedText {
+        let mut text = RenderedText::default();
+        for part_text in
+            parts.iter().filter_map(|part| self.get_format_text(part))
+        {
+            text.push(part_text);
+        }
+        text
     }
 
-    fn get_format_text(&self, part: &FormatPart) -> Option<String> {
+    fn get_format_text(&self, part: &FormatPart) -> Option<RenderedText> {
         match part {
-            FormatPart::Text(text) => Some(text.to_string()),
-
-            FormatPart::Title => self.data.get_title(),
-
-            FormatPart::Status => Some(self.data.get_status().to_string()),
-
-            FormatPart::Tag(tag_name) => self.data.get_tag(tag_name),
+            FormatPart::Text(text) => {
+                Some(RenderedText::literal(text.as_str()))
+            }
 
             // TODO: Deprecated
             FormatPart::MatchStatus(playback_status, text) => {
                 if self.data.is_status(playback_status) {
-                    Some(text.to_string())
+                    Some(RenderedText::literal(text.as_str()))
                 } else {
                     None
                 }
             }
 
-            FormatPart::Truncate(format_part_inner, max) => {
-                let max = *max;
-                self.get_format_text(format_part_inner.as_ref())
-                    .map(|text| {
-                        let mut text = text.to_string();
-                        if text.len() > max {
-                            let overflow_str_len = OVERFLOW_STR.len();
-                            if max >= overflow_str_len * 2 {
-                                text.truncate(max - overflow_str_len);
-                                text.push_str(OVERFLOW_STR);
-                            } else {
-                                text.truncate(max);
-                            }
-                        }
-                        text
-                    })
-            }
-
             FormatPart::HtmlEscape(format_part_inner) => self
-                .get_format_text(format_part_inner.as_ref())
-                .map(|text| htmlescape::encode_minimal(text.as_str())),
-
-            FormatPart::ProgressBar(bar_config) => {
-                if let Some(time) = self.data.get_time() {
-                    let width = bar_config.inner_width();
-                    let percent_complete = time.completion_percentage();
-                    let characters =
-                        (width as f32 * percent_complete).round() as usize;
-                    Some(bar_config.text_with_filled(characters))
-                } else {
-                    None
-                }
-            }
+                .map_format_text(format_part_inner, |text| {
+                    text.escape(&EscapeTarget::Html)
+                }),
+
+            FormatPart::Escape(format_part_inner, target) => self
+                .map_format_text(format_part_inner, |text| text.escape(target)),
+
+            FormatPart::Style(format_part_inner, style) => self
+                .map_format_text(format_part_inner, |text| {
+                    if let Some(target) = &self.style {
+                        let (start, end) = target.markup(style, &self.theme);
+                        text.style(start, end);
+                    }
+                }),
+
+            FormatPart::ProgressBar(bar_config) => self
+                .data
+                .get_time()
+                .as_ref()
+                .map(|time| {
+                    bar_config.text_with_percentage(
+                        time.completion_percentage().unwrap_or(0.0),
+                    )
+                })
+                .map(RenderedText::literal),
+
+            FormatPart::SmoothProgressBar { bar, ramp } => self
+                .data
+                .get_time()
+                .as_ref()
+                .map(|time| {
+                    bar.text_with_percentage_smooth(
+                        time.completion_percentage().unwrap_or(0.0),
+                        ramp,
+                    )
+                })
+                .map(RenderedText::literal),
+
+            FormatPart::VolumeBar(channel, bar_config) => self
+                .get_volume(channel)
+                .map(|volume| {
+                    bar_config.text_with_percentage(volume as f32 / 100.0)
+                })
+                .map(RenderedText::literal),
 
             FormatPart::Container(format_parts_inner) => Some(
                 self.get_format_text_for_parts(
@@ -113,6 +131,205 @@ impl StatusOutput {
                     self.get_format_text(format_part_false)
                 }
             }
+
+            FormatPart::Truncate(format_part_inner, max) => self
+                .map_format_text(format_part_inner, |text| {
+                    text.truncate(*max, OVERFLOW_STR, &TruncateSide::End)
+                }),
+
+            FormatPart::TruncateWith {
+                part,
+                width,
+                ellipsis,
+                side,
+            } => self.map_format_text(part, |text| {
+                text.truncate(*width, ellipsis.as_str(), side)
+            }),
+
+            FormatPart::Pad(format_part_inner, width, alignment, fill) => self
+                .map_format_text(format_part_inner, |text| {
+                    text.pad(*width, alignment, *fill)
+                }),
+
+            FormatPart::FixedWidth {
+                part,
+                width,
+                align,
+                fill,
+                ellipsis,
+                side,
+            } => {
+                let mut text =
+                    self.get_format_text(part.as_ref()).unwrap_or_default();
+                text.truncate(*width, ellipsis.as_str(), side);
+                text.pad(*width, align, *fill);
+                Some(text)
+            }
+
+            FormatPart::Upper(format_part_inner) => self
+                .map_format_text(format_part_inner, |text| {
+                    text.map_chars(|_, c| c.to_uppercase().collect())
+                }),
+
+            FormatPart::Lower(format_part_inner) => self
+                .map_format_text(format_part_inner, |text| {
+                    text.map_chars(|_, c| c.to_lowercase().collect())
+                }),
+
+            FormatPart::TitleCase(format_part_inner) => self
+                .map_format_text(format_part_inner, |text| {
+                    text.map_chars(text::title_case_char)
+                }),
+
+            FormatPart::Trim(format_part_inner) => {
+                self.map_format_text(format_part_inner, RenderedText::trim)
+            }
+
+            FormatPart::Replace(format_part_inner, regex, replacement) => self
+                .map_format_text(format_part_inner, |text| {
+                    text.replace(regex, replacement.as_str())
+                }),
+
+            // Everything else is derived from cmus' data, and is escaped
+            // with the default escape, once the status line is finished.
+            part => self.get_data_text(part).map(RenderedText::data),
+        }
+    }
+
+    /// Renders the inner `FormatPart` and transforms its text,
+    /// which stays unescaped until the status line is finished.
+    fn map_format_text<F>(
+        &self,
+        part: &FormatPart,
+        transform: F,
+    ) -> Option<RenderedText>
+    where
+        F: FnOnce(&mut RenderedText),
+    {
+        self.get_format_text(part).map(|mut text| {
+            transform(&mut text);
+            text
+        })
+    }
+
+    /// Returns the unescaped text of `FormatPart`s,
+    /// which are derived from cmus' data.
+    fn get_data_text(&self, part: &FormatPart) -> Option<String> {
+        match part {
+            FormatPart::Title => self.data.get_title(),
+
+            FormatPart::Stream => self.data.get_stream().cloned(),
+
+            FormatPart::Status => Some(self.data.get_status().to_string()),
+
+            FormatPart::Tag(tag_name) => self.data.get_tag(tag_name),
+
+            FormatPart::TrackNumber { pad } => self
+                .data
+                .get_track_number()
+                .map(|track| format!("{:0pad$}", track.number, pad = pad)),
+
+            FormatPart::TrackTotal { pad } => self
+                .data
+                .get_track_number()
+                .and_then(|track| track.total)
+                .map(|total| format!("{:0pad$}", total, pad = pad)),
+
+            FormatPart::DiscNumber { pad } => self
+                .data
+                .get_disc_number()
+                .map(|disc| format!("{:0pad$}", disc.number, pad = pad)),
+
+            FormatPart::DiscTotal { pad } => self
+                .data
+                .get_disc_number()
+                .and_then(|disc| disc.total)
+                .map(|total| format!("{:0pad$}", total, pad = pad)),
+
+            FormatPart::Year => {
+                self.data.get_year().map(|year| year.to_string())
+            }
+
+            FormatPart::Date => {
+                self.data.get_date().map(|date| date.to_string())
+            }
+
+            FormatPart::Position(time_format) => self
+                .data
+                .get_time()
+                .as_ref()
+                .map(|time| time_format.format(time.position)),
+
+            FormatPart::Duration(time_format) => self
+                .data
+                .get_time()
+                .as_ref()
+                .and_then(|time| time.duration)
+                .map(|duration| time_format.format(duration)),
+
+            FormatPart::Remaining(time_format) => self
+                .data
+                .get_time()
+                .as_ref()
+                .and_then(|time| time.remaining())
+                .map(|remaining| {
+                    if time_format.is_human() {
+                        format!("{} left", time_format.format(remaining))
+                    } else {
+                        time_format.format(remaining)
+                    }
+                }),
+
+            FormatPart::Setting(setting_name) => {
+                self.data.get_settings().get_raw(setting_name).cloned()
+            }
+
+            FormatPart::Raw(key) => self.data.get_raw(key).cloned(),
+
+            FormatPart::Percent {
+                decimals,
+                suffix,
+                rounding,
+            } => self
+                .data
+                .get_time()
+                .as_ref()
+                .and_then(CmusTime::completion_percentage)
+                .map(|percentage| {
+                    let decimals = (*decimals).min(MAX_PERCENT_DECIMALS);
+                    let factor = 10_f32.powi(decimals as i32);
+                    let percent =
+                        rounding.round(percentage * 100.0 * factor) / factor;
+                    format!(
+                        "{:.decimals$}{}",
+                        percent,
+                        suffix,
+                        decimals = decimals
+                    )
+                }),
+
+            FormatPart::Volume(channel) => {
+                self.get_volume(channel).map(|volume| volume.to_string())
+            }
+
+            // Handled by `get_format_text`.
+            _ => None,
+        }
+    }
+
+    fn get_volume(&self, channel: &VolumeChannel) -> Option<u8> {
+        let settings = self.data.get_settings();
+        match channel {
+            VolumeChannel::Left => settings.vol_left,
+            VolumeChannel::Right => settings.vol_right,
+            VolumeChannel::Average => {
+                match (settings.vol_left, settings.vol_right) {
+                    (Some(left), Some(right)) => {
+                        Some(((left as f32 + right as f32) / 2.0).round() as u8)
+                    }
+                    (left, right) => left.or(right),
+                }
+            }
         }
     }
 
@@ -133,11 +350,127 @@ impl StatusOutput {
 
             FormatExpression::Not(expr) => !self.is_expression_true(expr),
 
+            FormatExpression::All(exprs) => {
+                exprs.iter().all(|expr| self.is_expression_true(expr))
+            }
+
+            FormatExpression::Any(exprs) => {
+                exprs.iter().any(|expr| self.is_expression_true(expr))
+            }
+
+            FormatExpression::Expr(expr) => self.is_expression_true(expr),
+
             FormatExpression::IsStatus(playback_status) => {
-                self.data.is_status(&playback_status)
+                self.data.is_status(playback_status)
+            }
+
+            FormatExpression::HasTag(tag_name) => self.data.has_tag(tag_name),
+
+            FormatExpression::TagEquals(tag_name, value) => {
+                self.data.get_tag(tag_name).as_ref() == Some(value)
+            }
+
+            FormatExpression::TagMatches(tag_name, regex) => self
+                .data
+                .get_tag(tag_name)
+                .map(|value| regex.is_match(value.as_str()))
+                .unwrap_or(false),
+
+            FormatExpression::TagMatchesNoCase(tag_name, regex) => self
+                .data
+                .get_tag(tag_name)
+                .map(|value| regex.is_match(value.as_str()))
+                .unwrap_or(false),
+
+            FormatExpression::TagCompare(tag_name, op, number) => self
+                .data
+                .get_tag_number(tag_name)
+                .map(|value| op.compare(value, *number))
+                .unwrap_or(false),
+
+            FormatExpression::FileMatches(glob) => self
+                .data
+                .get_file()
+                .map(|file| glob.matches_path(file))
+                .unwrap_or(false),
+
+            FormatExpression::HasExtension(extension) => self
+                .data
+                .get_file()
+                .and_then(|file| file.extension())
+                .map(|file_extension| {
+                    file_extension
+                        .to_string_lossy()
+                        .eq_ignore_ascii_case(extension.trim_start_matches('.'))
+                })
+                .unwrap_or(false),
+
+            FormatExpression::IsStream => self.data.is_stream(),
+
+            FormatExpression::IsShuffle => {
+                self.data.get_settings().is_shuffle()
+            }
+
+            FormatExpression::IsRepeat => {
+                self.data.get_settings().repeat.unwrap_or(false)
+            }
+
+            FormatExpression::IsRepeatCurrent => {
+                self.data.get_settings().repeat_current.unwrap_or(false)
+            }
+
+            FormatExpression::IsContinue => {
+                self.data.get_settings().continue_playback.unwrap_or(false)
             }
 
-            FormatExpression::HasTag(tag_name) => self.data.has_tag(&tag_name),
+            FormatExpression::IsPlayLibrary => {
+                self.data.get_settings().play_library.unwrap_or(false)
+            }
+
+            FormatExpression::IsPlaySorted => {
+                self.data.get_settings().play_sorted.unwrap_or(false)
+            }
+
+            FormatExpression::IsSoftvol => {
+                self.data.get_settings().softvol.unwrap_or(false)
+            }
+
+            FormatExpression::AaaMode(aaa_mode) => {
+                self.data.get_settings().aaa_mode.as_ref() == Some(aaa_mode)
+            }
+
+            FormatExpression::HasTime => self.data.get_time().is_some(),
+
+            FormatExpression::PositionAtLeast(seconds) => self
+                .data
+                .get_time()
+                .as_ref()
+                .map(|time| time.position >= *seconds)
+                .unwrap_or(false),
+
+            FormatExpression::RemainingLessThan(seconds) => self
+                .data
+                .get_time()
+                .as_ref()
+                .and_then(CmusTime::remaining)
+                .map(|remaining| remaining < *seconds)
+                .unwrap_or(false),
+
+            FormatExpression::DurationLongerThan(seconds) => self
+                .data
+                .get_time()
+                .as_ref()
+                .and_then(|time| time.duration)
+                .map(|duration| duration > *seconds)
+                .unwrap_or(false),
+
+            FormatExpression::PercentAbove(percent) => self
+                .data
+                .get_time()
+                .as_ref()
+                .and_then(CmusTime::completion_percentage)
+                .map(|percentage| percentage * 100.0 > *percent)
+                .unwrap_or(false),
         }
     }
 }
@@ -148,6 +481,7 @@ impl fmt::Display for StatusOutput {
             f,
             "{}",
             self.get_format_text_for_parts(self.format.iter().collect())
+                .finish(self.escape.as_ref())
         )
     }
 }
@@ -0,0 +1,239 @@
+//! Text rendered from `FormatPart`s, which remembers which parts of it
+//! are derived from cmus' data, and where style markup was added.
+//! Text transformations work on the plain, unescaped and unstyled text,
+//! escaping is only applied to the finished status line.
+
+use super::format::{Alignment, EscapeTarget, FormatRegex, TruncateSide};
+use super::text;
+use std::ops::Range;
+
+#[derive(Default)]
+pub struct RenderedText {
+    segments: Vec<Segment>,
+}
+
+enum Segment {
+    Text(TextSegment),
+    /// The markup of a `Style` `FormatPart`, which isn't part of
+    /// the text's width, isn't transformed and isn't escaped.
+    Markup(String),
+}
+
+struct TextSegment {
+    text:    String,
+    /// Derived from cmus' data, so it is escaped with the default escape.
+    data:    bool,
+    /// The targets of the `Escape` `FormatPart`s around this segment,
+    /// innermost first. Replace the default escape.
+    escapes: Vec<EscapeTarget>,
+}
+
+impl RenderedText {
+    /// Text from the config itself, which is only escaped
+    /// by `Escape` `FormatPart`s.
+    pub fn literal<S: Into<String>>(text: S) -> Self {
+        Self {
+            segments: vec![Segment::Text(TextSegment::literal(text.into()))],
+        }
+    }
+
+    /// Text derived from cmus' data.
+    pub fn data<S: Into<String>>(text: S) -> Self {
+        Self {
+            segments: vec![Segment::Text(TextSegment {
+                text:    text.into(),
+                data:    true,
+                escapes: Vec::new(),
+            })],
+        }
+    }
+
+    pub fn push(&mut self, other: RenderedText) {
+        self.segments.extend(other.segments);
+    }
+
+    /// Wraps the text in the markup of a style.
+    pub fn style(&mut self, start: String, end: String) {
+        self.segments.insert(0, Segment::Markup(start));
+        self.segments.push(Segment::Markup(end));
+    }
+
+    /// Returns the unescaped text, without any style markup.
+    pub fn plain(&self) -> String {
+        self.text_segments()
+            .map(|segment| segment.text.as_str())
+            .collect()
+    }
+
+    /// Escapes all of the text for the `target`, when it's finished.
+    pub fn escape(&mut self, target: &EscapeTarget) {
+        for segment in self.text_segments_mut() {
+            segment.escapes.push(target.clone());
+        }
+    }
+
+    /// Maps each character, given the previous character of the text.
+    pub fn map_chars<F>(&mut self, mut map: F)
+    where
+        F: FnMut(Option<char>, char) -> String,
+    {
+        let mut prev = None;
+        for segment in self.text_segments_mut() {
+            let mut mapped = String::with_capacity(segment.text.len());
+            for c in segment.text.chars() {
+                mapped.push_str(map(prev, c).as_str());
+                prev = Some(c);
+            }
+            segment.text = mapped;
+        }
+    }
+
+    pub fn trim(&mut self) {
+        let plain = self.plain();
+        self.replace_range(plain.trim_end().len()..plain.len(), None);
+        self.replace_range(0..plain.len() - plain.trim_start().len(), None);
+    }
+
+    /// Truncates the text to at most `width` columns,
+    /// see `text::truncated_range`.
+    pub fn truncate(
+        &mut self,
+        width: usize,
+        ellipsis: &str,
+        side: &TruncateSide,
+    ) {
+        let plain = self.plain();
+        if let Some((range, ellipsis)) =
+            text::truncated_range(plain.as_str(), width, ellipsis, side)
+        {
+            self.replace_range(
+                range,
+                Some(TextSegment::literal(ellipsis.into())),
+            );
+        }
+    }
+
+    /// Pads the text to at least `width` columns, see `text::padding`.
+    pub fn pad(&mut self, width: usize, alignment: &Alignment, fill: char) {
+        let text_width = text::display_width(self.plain().as_str());
+        let (start, end) = text::padding(text_width, width, alignment, fill);
+        self.segments
+            .insert(0, Segment::Text(TextSegment::literal(start)));
+        self.segments.push(Segment::Text(TextSegment::literal(end)));
+    }
+
+    /// Replaces all matches of the `regex` with the `replacement`.
+    /// The replaced text is escaped like the text at the match's start.
+    pub fn replace(&mut self, regex: &FormatRegex, replacement: &str) {
+        let plain = self.plain();
+        let replacements = regex.replacements(plain.as_str(), replacement);
+        for (range, replaced) in replacements.into_iter().rev() {
+            let segment = match self.segment_at(range.start) {
+                Some(segment) => segment.with_text(replaced),
+                None => TextSegment::literal(replaced),
+            };
+            self.replace_range(range, Some(segment));
+        }
+    }
+
+    /// Returns the escaped and styled text, using the `default` escape
+    /// for text derived from cmus' data.
+    pub fn finish(self, default: Option<&EscapeTarget>) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(segment) => segment.escaped(default),
+                Segment::Markup(markup) => markup.clone(),
+            })
+            .collect()
+    }
+
+    fn text_segments(&self) -> impl Iterator<Item = &TextSegment> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Text(segment) => Some(segment),
+            Segment::Markup(_) => None,
+        })
+    }
+
+    fn text_segments_mut(&mut self) -> impl Iterator<Item = &mut TextSegment> {
+        self.segments
+            .iter_mut()
+            .filter_map(|segment| match segment {
+                Segment::Text(segment) => Some(segment),
+                Segment::Markup(_) => None,
+            })
+    }
+
+    /// Returns the segment containing the given byte offset of the text.
+    fn segment_at(&self, offset: usize) -> Option<&TextSegment> {
+        let mut start = 0;
+        self.text_segments().find(|segment| {
+            start += segment.text.len();
+            offset < start
+        })
+    }
+
+    /// Replaces the given byte range of the text with the `replacement`.
+    /// Style markup inside the range is kept, so it stays balanced.
+    fn replace_range(
+        &mut self,
+        range: Range<usize>,
+        mut replacement: Option<TextSegment>,
+    ) {
+        let mut segments = Vec::with_capacity(self.segments.len() + 2);
+        let mut start = 0;
+        for segment in self.segments.drain(..) {
+            let segment = match segment {
+                Segment::Text(segment) => segment,
+                markup => {
+                    segments.push(markup);
+                    continue;
+                }
+            };
+            let end = start + segment.text.len();
+            let before = &segment.text[..range.start.clamp(start, end) - start];
+            let after = &segment.text[range.end.clamp(start, end) - start..];
+            if !before.is_empty() {
+                segments
+                    .push(Segment::Text(segment.with_text(before.to_string())));
+            }
+            if range.start < end {
+                segments.extend(replacement.take().map(Segment::Text));
+            }
+            if !after.is_empty() {
+                segments
+                    .push(Segment::Text(segment.with_text(after.to_string())));
+            }
+            start = end;
+        }
+        segments.extend(replacement.map(Segment::Text));
+        self.segments = segments;
+    }
+}
+
+impl TextSegment {
+    fn literal(text: String) -> Self {
+        Self {
+            text,
+            data: false,
+            escapes: Vec::new(),
+        }
+    }
+
+    fn with_text(&self, text: String) -> Self {
+        Self {
+            text,
+            data: self.data,
+            escapes: self.escapes.clone(),
+        }
+    }
+
+    fn escaped(&self, default: Option<&EscapeTarget>) -> String {
+        match (self.escapes.is_empty(), default) {
+            (true, Some(target)) if self.data => target.escape(&self.text),
+            _ => self.escapes.iter().fold(self.text.clone(), |text, target| {
+                target.escape(text.as_str())
+            }),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Helpers for measuring and cutting text by its terminal display width,
+//! always at grapheme boundaries.
+
+use super::format::{Alignment, TruncateSide};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Returns the text's width in terminal columns,
+/// where wide characters (such as CJK) take up two columns.
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Returns the byte range, which is removed to truncate the text
+/// to at most `max_width` columns, and the ellipsis to insert in its place.
+/// The `ellipsis` is omitted if it would take up more than
+/// half of the `max_width`.
+/// Returns `None` if the text already fits into `max_width` columns.
+pub fn truncated_range<'a>(
+    text: &str,
+    max_width: usize,
+    ellipsis: &'a str,
+    side: &TruncateSide,
+) -> Option<(Range<usize>, &'a str)> {
+    if display_width(text) <= max_width {
+        return None;
+    }
+
+    let ellipsis_width = display_width(ellipsis);
+    let (ellipsis, available_width) = if max_width >= ellipsis_width * 2 {
+        (ellipsis, max_width - ellipsis_width)
+    } else {
+        ("", max_width)
+    };
+
+    let range = match side {
+        TruncateSide::Start => {
+            0..text.len() - take_end(text, available_width).len()
+        }
+        TruncateSide::Middle => {
+            let start_width = available_width.div_ceil(2);
+            let start = take_start(text, start_width);
+            let end = take_end(text, available_width - display_width(start));
+            start.len()..text.len() - end.len()
+        }
+        TruncateSide::End => {
+            take_start(text, available_width).len()..text.len()
+        }
+    };
+    Some((range, ellipsis))
+}
+
+/// Returns the longest start of the text, which fits into `width` columns.
+pub fn take_start(text: &str, width: usize) -> &str {
+    let mut taken_width = 0;
+    let mut end = 0;
+    for (index, grapheme) in text.grapheme_indices(true) {
+        taken_width += display_width(grapheme);
+        if taken_width > width {
+            break;
+        }
+        end = index + grapheme.len();
+    }
+    &text[..end]
+}
+
+/// Returns the longest end of the text, which fits into `width` columns.
+pub fn take_end(text: &str, width: usize) -> &str {
+    let mut taken_width = 0;
+    let mut start = text.len();
+    for (index, grapheme) in text.grapheme_indices(true).rev() {
+        taken_width += display_width(grapheme);
+        if taken_width > width {
+            break;
+        }
+        start = index;
+    }
+    &text[start..]
+}
+
+/// Returns the padding with the `fill` character, which pads a text
+/// that is `text_width` columns wide to at least `width` columns.
+/// If the `fill` character is wider than the remaining padding,
+/// spaces are used for the rest.
+pub fn padding(
+    text_width: usize,
+    width: usize,
+    alignment: &Alignment,
+    fill: char,
+) -> (String, String) {
+    let padding_width = width.saturating_sub(text_width);
+    let padding = |padding_width: usize| {
+        let fill_width = UnicodeWidthChar::width(fill).unwrap_or(0).max(1);
+        let fills = padding_width / fill_width;
+        let mut padding = fill.to_string().repeat(fills);
+        padding
+            .push_str(" ".repeat(padding_width - fills * fill_width).as_str());
+        padding
+    };
+
+    match alignment {
+        Alignment::Left => (String::new(), padding(padding_width)),
+        Alignment::Right => (padding(padding_width), String::new()),
+        Alignment::Center => {
+            let left_width = padding_width / 2;
+            (padding(left_width), padding(padding_width - left_width))
+        }
+    }
+}
+
+/// Title cases a single character, which follows the `prev` character.
+/// The first letter of each word is uppercased, and the rest lowercased.
+/// Words start after any character, which isn't alphanumeric or `'`.
+pub fn title_case_char(prev: Option<char>, c: char) -> String {
+    let starts_word = prev
+        .map(|p: char| !p.is_alphanumeric() && p != '\'')
+        .unwrap_or(true);
+    if starts_word {
+        c.to_uppercase().collect()
+    } else {
+        c.to_lowercase().collect()
+    }
+}
@@ -0,0 +1,203 @@
+//! Sources of `cmus-remote -Q` formatted output.
+//! Implement `CmusSource` to feed `StatusOutput` from anywhere else,
+//! and pass it to `get_cmus_status_from`.
+
+use super::client;
+use crate::config::{Config, ServerConfig};
+use crate::error::prelude::*;
+use std::convert::TryFrom;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::Command;
+
+pub mod prelude {
+    pub use super::CmusSource;
+    pub use super::SourceConfig;
+    pub use super::{AutoSource, CmusRemoteSource, SocketSource, TcpSource};
+    pub use super::{FileSource, FixtureSource, StdinSource};
+}
+
+/// Provides output in the format of `cmus-remote -Q`,
+/// which is parsed into `CmusData`.
+pub trait CmusSource {
+    fn get_output(&mut self) -> MyResult<Vec<u8>>;
+}
+
+/// Which `CmusSource` to use, configured with the `source` config key,
+/// or the `--source` command-line option.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub enum SourceConfig {
+    /// Query the remote cmus if a `server` is configured,
+    /// otherwise query cmus' socket, falling back to `cmus-remote`.
+    #[default]
+    Auto,
+    CmusRemote,
+    Socket,
+    Tcp,
+    Stdin,
+    File(PathBuf),
+}
+
+impl SourceConfig {
+    pub fn create_source(
+        &self,
+        config: &Config,
+    ) -> MyResult<Box<dyn CmusSource>> {
+        Ok(match self {
+            SourceConfig::Auto => Box::new(AutoSource {
+                server:  config.server.clone(),
+                verbose: config.verbose,
+            }),
+            SourceConfig::CmusRemote => Box::new(CmusRemoteSource),
+            SourceConfig::Socket => Box::new(SocketSource::default()),
+            SourceConfig::Tcp => Box::new(TcpSource::new(
+                config.server.clone().ok_or(Error::NoServerConfig)?,
+            )),
+            SourceConfig::Stdin => Box::new(StdinSource),
+            SourceConfig::File(path) => Box::new(FileSource::new(path)),
+        })
+    }
+}
+
+impl TryFrom<&str> for SourceConfig {
+    type Error = Error;
+
+    fn try_from(name: &str) -> MyResult<Self> {
+        match name {
+            "auto" => Ok(SourceConfig::Auto),
+            "cmus-remote" => Ok(SourceConfig::CmusRemote),
+            "socket" => Ok(SourceConfig::Socket),
+            "tcp" => Ok(SourceConfig::Tcp),
+            "stdin" => Ok(SourceConfig::Stdin),
+            _ => Err(Error::InvalidSource(name.to_string())),
+        }
+    }
+}
+
+/// Queries cmus' socket, falling back to `cmus-remote`.
+/// Only queries the remote cmus, if a `server` is configured.
+pub struct AutoSource {
+    pub server:  Option<ServerConfig>,
+    pub verbose: bool,
+}
+
+impl CmusSource for AutoSource {
+    fn get_output(&mut self) -> MyResult<Vec<u8>> {
+        if let Some(server) = &self.server {
+            return client::get_tcp_output(server);
+        }
+        SocketSource::default().get_output().or_else(|e| {
+            if self.verbose {
+                eprintln!("WARNING: {}", e.message());
+            }
+            CmusRemoteSource.get_output()
+        })
+    }
+}
+
+/// Runs `cmus-remote -Q`.
+pub struct CmusRemoteSource;
+
+impl CmusSource for CmusRemoteSource {
+    fn get_output(&mut self) -> MyResult<Vec<u8>> {
+        match Command::new("cmus-remote").arg("-Q").output() {
+            Ok(output) => {
+                if output.status.success() {
+                    Ok(output.stdout)
+                } else {
+                    Err(Error::CmusError {
+                        status: output.status,
+                        stderr: String::from_utf8_lossy(&output.stderr)
+                            .into_owned(),
+                    })
+                }
+            }
+            Err(_) => Err(Error::CmusNotInstalled),
+        }
+    }
+}
+
+/// Queries cmus' UNIX socket.
+/// Without a path, the socket is looked up at the default locations.
+#[derive(Default)]
+pub struct SocketSource {
+    pub path: Option<PathBuf>,
+}
+
+impl CmusSource for SocketSource {
+    fn get_output(&mut self) -> MyResult<Vec<u8>> {
+        match &self.path {
+            Some(path) => client::get_socket_output_from(path),
+            None => client::get_socket_output(),
+        }
+    }
+}
+
+/// Queries a remote cmus over TCP/IP.
+pub struct TcpSource {
+    pub server: ServerConfig,
+}
+
+impl TcpSource {
+    pub fn new(server: ServerConfig) -> Self {
+        Self { server }
+    }
+}
+
+impl CmusSource for TcpSource {
+    fn get_output(&mut self) -> MyResult<Vec<u8>> {
+        client::get_tcp_output(&self.server)
+    }
+}
+
+/// Reads the output from a file.
+pub struct FileSource {
+    pub path: PathBuf,
+}
+
+impl FileSource {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CmusSource for FileSource {
+    fn get_output(&mut self) -> MyResult<Vec<u8>> {
+        fs::read(&self.path).map_err(|e| {
+            Error::FailedReadingInput(self.path.clone(), e.to_string())
+        })
+    }
+}
+
+/// Reads the output from stdin, until EOF.
+pub struct StdinSource;
+
+impl CmusSource for StdinSource {
+    fn get_output(&mut self) -> MyResult<Vec<u8>> {
+        let mut output = Vec::new();
+        io::stdin().read_to_end(&mut output).map_err(|e| {
+            Error::FailedReadingInput(PathBuf::from("-"), e.to_string())
+        })?;
+        Ok(output)
+    }
+}
+
+/// Always returns the same output.
+pub struct FixtureSource {
+    pub output: Vec<u8>,
+}
+
+impl FixtureSource {
+    pub fn new<O: Into<Vec<u8>>>(output: O) -> Self {
+        Self {
+            output: output.into(),
+        }
+    }
+}
+
+impl CmusSource for FixtureSource {
+    fn get_output(&mut self) -> MyResult<Vec<u8>> {
+        Ok(self.output.clone())
+    }
+}
@@ -0,0 +1,56 @@
+//! JSON output for Waybar's custom modules, with `"return-type": "json"`.
+//! See <https://github.com/Alexays/Waybar/wiki/Module:-Custom>.
+
+use super::data::CmusData;
+use super::output::{EscapeTarget, Format};
+use super::source::CmusSource;
+use super::{build_status_output, get_cmus_data_from};
+use crate::config::Config;
+use crate::error::prelude::*;
+
+/// Returns the Waybar JSON object for the given `CmusSource`'s output.
+/// The `text` uses the config's `format`, the optional `tooltip` and `alt`
+/// use the formats in the config's `waybar` section.
+/// The `class` is the lowercase `CmusPlaybackStatus`, and the `percentage`
+/// is the playback's completion percentage, if the duration is known.
+pub fn get_waybar_json_from(
+    source: &mut dyn CmusSource,
+    config: &Config,
+) -> MyResult<String> {
+    let cmus_data = get_cmus_data_from(source, config)?;
+    let json = |text: &str| EscapeTarget::Json.escape(text);
+    let render = |format: &Format| {
+        build_status_output(
+            cmus_data.clone(),
+            format.clone(),
+            config,
+            config.escape.clone(),
+        )
+        .map(|output| json(&output.to_string()))
+    };
+
+    let mut fields = vec![format!(r#""text":"{}""#, render(&config.format)?)];
+    if let Some(alt) = &config.waybar.alt {
+        fields.push(format!(r#""alt":"{}""#, render(alt)?));
+    }
+    if let Some(tooltip) = &config.waybar.tooltip {
+        fields.push(format!(r#""tooltip":"{}""#, render(tooltip)?));
+    }
+    fields.push(format!(
+        r#""class":"{}""#,
+        cmus_data.get_status().to_string().to_lowercase()
+    ));
+    if let Some(percentage) = get_percentage(&cmus_data) {
+        fields.push(format!(r#""percentage":{}"#, percentage));
+    }
+
+    Ok(format!("{{{}}}", fields.join(",")))
+}
+
+fn get_percentage(cmus_data: &CmusData) -> Option<u8> {
+    cmus_data
+        .get_time()
+        .as_ref()
+        .and_then(|time| time.completion_percentage())
+        .map(|percentage| (percentage * 100.0).round() as u8)
+}
@@ -1,8 +1,13 @@
-use crate::cmus_status::output::Format;
+use crate::cmus_status::data::{InvalidUtf8Mode, ParseMode};
+use crate::cmus_status::output::{Color, EscapeTarget, Format};
+use crate::cmus_status::output::{StyleTarget, Theme};
+use crate::cmus_status::source::SourceConfig;
 use crate::error::prelude::*;
 use std::fs::File;
 use std::io::Read;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
 pub const DEFAULT_CONFIG: &str = include_str!("../../config.toml");
 const KEYWORD_CONFIG_DIR: &str = "<CONFIG_DIR>";
@@ -14,13 +19,133 @@ const CONFIG_FILES: [&str; 3] = [
 
 #[derive(Deserialize)]
 pub struct Config {
-    pub format: Format,
+    pub format:       Format,
+    /// `Strict` or `Lenient`, how to handle unknown `cmus-remote` output.
+    #[serde(default)]
+    pub parse_mode:   ParseMode,
+    /// `Replace` or `Error`, how to handle invalid UTF-8 from `cmus-remote`.
+    #[serde(default)]
+    pub invalid_utf8: InvalidUtf8Mode,
+    /// Print warnings to stderr.
+    #[serde(default)]
+    pub verbose:      bool,
+    /// Connect to a remote cmus over TCP/IP,
+    /// instead of querying the local cmus.
+    #[serde(default)]
+    pub server:       Option<ServerConfig>,
+    /// Where to get cmus' status from.
+    #[serde(default)]
+    pub source:       SourceConfig,
+    /// Escape the text of all `FormatPart`s derived from cmus' data,
+    /// such as titles and tags, for the given `EscapeTarget`.
+    #[serde(default)]
+    pub escape:       Option<EscapeTarget>,
+    /// What to render the styles of `Style` `FormatPart`s for.
+    #[serde(default)]
+    pub style_target: Option<StyleTarget>,
+    /// Named colors, which can be used in `Style` `FormatPart`s.
+    #[serde(default)]
+    pub theme:        Theme,
+    /// Settings for the `i3bar` command.
+    #[serde(default)]
+    pub i3bar:        I3barConfig,
+    /// Settings for the `waybar` output.
+    #[serde(default)]
+    pub waybar:       WaybarConfig,
 }
 
-impl Config {
+/// A cmus instance listening on TCP/IP, started with
+/// `cmus --listen host:port` and with the `passwd` option set.
+#[derive(Clone, Deserialize)]
+pub struct ServerConfig {
+    pub host:            String,
+    #[serde(default = "default_server_port")]
+    pub port:            u16,
+    pub password:        String,
+    /// In seconds.
+    #[serde(default = "default_server_connect_timeout")]
+    pub connect_timeout: f32,
+}
+
+/// Settings for the `i3bar` command.
+#[derive(Clone, Deserialize)]
+pub struct I3barConfig {
+    /// How often to update the blocks, in seconds.
+    #[serde(default = "default_i3bar_interval")]
+    pub interval: f32,
+    /// The blocks to print. Without any blocks,
+    /// a single block with the config's `format` is printed.
+    #[serde(default)]
+    pub blocks:   Vec<I3barBlockConfig>,
+}
+
+/// A block of the i3bar protocol.
+#[derive(Clone, Deserialize)]
+pub struct I3barBlockConfig {
+    #[serde(default = "default_i3bar_block_name")]
+    pub name:         String,
+    pub format:       Format,
+    /// Used when the bar is running out of space.
+    #[serde(default)]
+    pub short_format: Option<Format>,
+    #[serde(default)]
+    pub color:        Color,
+}
+
+/// Settings for the `waybar` output.
+/// The `text` field always uses the config's `format`.
+#[derive(Clone, Default, Deserialize)]
+pub struct WaybarConfig {
+    #[serde(default)]
+    pub tooltip: Option<Format>,
+    #[serde(default)]
+    pub alt:     Option<Format>,
+}
+
+impl Default for I3barConfig {
+    fn default() -> Self {
+        Self {
+            interval: default_i3bar_interval(),
+            blocks:   Vec::new(),
+        }
+    }
+}
+
+fn default_i3bar_interval() -> f32 {
+    1.0
+}
+
+pub(crate) fn default_i3bar_block_name() -> String {
+    "cmus".to_string()
+}
+
+fn default_server_port() -> u16 {
+    3000
+}
+
+fn default_server_connect_timeout() -> f32 {
+    1.0
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
     /// RON or TOML string
-    pub fn from_str(s: &str) -> MyResult<Self> {
-        Self::from_ron(s).or_else(|_| Self::from_toml(s))
+    fn from_str(s: &str) -> MyResult<Self> {
+        Self::from_ron(s)
+            .or_else(|_| Self::from_toml(s))?
+            .validate()
+    }
+}
+
+impl Config {
+    /// Check the values, which the types alone don't restrict enough.
+    fn validate(self) -> MyResult<Self> {
+        if let Some(server) = &self.server {
+            validate_seconds("server.connect_timeout", server.connect_timeout)?;
+        }
+        validate_seconds("i3bar.interval", self.i3bar.interval)?;
+        Ok(self)
     }
 
     fn from_ron(ron: &str) -> MyResult<Self> {
@@ -55,7 +180,15 @@ pub fn get_config() -> MyResult<Config> {
 }
 
 fn default_config() -> MyResult<Config> {
-    Config::from_toml(DEFAULT_CONFIG)
+    Config::from_toml(DEFAULT_CONFIG)?.validate()
+}
+
+/// Durations have to be positive, and small enough for a `Duration`.
+fn validate_seconds(name: &str, seconds: f32) -> MyResult<()> {
+    match Duration::try_from_secs_f32(seconds) {
+        Ok(duration) if !duration.is_zero() => Ok(()),
+        _ => Err(Error::InvalidSeconds(name.to_string(), seconds)),
+    }
 }
 
 fn get_config_file() -> Option<PathBuf> {
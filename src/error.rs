@@ -12,33 +12,71 @@ pub type MyResult<T> = Result<T, Error>;
 #[derive(Debug)]
 pub enum Error {
     CmusNotInstalled,
+    CmusSocketNotFound,
+    CmusSocketError(PathBuf, String),
+    CmusConnectionFailed(String, String),
+    CmusConnectionTimeout(String),
+    CmusAuthenticationFailed(String),
     CmusError { status: ExitStatus, stderr: String },
     CmusParseError(String),
     CmusUnknownData(String),
     CmusExpectDataArguments(usize, String),
     CmusUnknownStatus(String),
+    CmusInvalidSetting(String),
+    CmusInvalidUtf8(String),
+    CmusInvalidTag(String),
     CouldntParseTimeToNumber(String),
     CmusMissingData(String),
     CmusStatusNoData,
+    NoServerConfig,
+    InvalidSource(String),
+    InvalidOutput(String),
+    I3barStdinSource,
+    FailedReadingInput(PathBuf, String),
     NoFormat,
     InvalidFormatKeyword(String),
     ProgressBarConfigMinLen(usize, String),
+    ProgressBarConfigInvalid(String),
+    ProgressBarRampEmpty,
+    InvalidTimeFormat(String),
+    InvalidRegex(String, String),
+    InvalidExpression(String, String),
+    InvalidGlob(String, String),
+    InvalidColor(String),
+    InvalidSeconds(String, f32),
     FailedParsingConfig(Option<PathBuf>, String),
     NoConfig,
     InvalidArgument(String),
     InvalidCommandLen(String),
     InvalidCommandOption(String, String),
-    CommandTakesNoOptions(String),
+    OptionMissingValue(String),
 }
 
 impl Error {
-    fn message(&self) -> String {
+    pub(crate) fn message(&self) -> String {
         match self {
             Error::CmusNotInstalled => "cmus is not installed.".to_string(),
+            Error::CmusSocketNotFound => {
+                "couldn't find cmus' socket, is cmus running?".to_string()
+            }
+            Error::CmusSocketError(path, e) => {
+                format!("failed querying cmus' socket at {:?}\n{}", path, e)
+            }
             Error::CmusError { status, stderr } => format!(
                 "cmus exited with status code {}\nstderr: {}",
                 status, stderr,
             ),
+            Error::CmusConnectionFailed(address, e) => {
+                format!("failed connecting to cmus at {}\n{}", address, e)
+            }
+            Error::CmusConnectionTimeout(address) => {
+                format!("timed out connecting to cmus at {}", address)
+            }
+            Error::CmusAuthenticationFailed(address) => format!(
+                "cmus at {} rejected the password, check the `password` of \
+                 the `server` config",
+                address
+            ),
             Error::CmusParseError(msg) => {
                 format!("cmus-remote parsing error: {}", msg)
             }
@@ -54,6 +92,15 @@ impl Error {
             Error::CmusUnknownStatus(status) => {
                 format!("cmus-remote returned unknown status: {}", status)
             }
+            Error::CmusInvalidSetting(setting) => {
+                format!("cmus-remote returned invalid setting: {}", setting)
+            }
+            Error::CmusInvalidUtf8(data_line) => {
+                format!("cmus-remote returned invalid UTF-8: {}", data_line)
+            }
+            Error::CmusInvalidTag(value) => {
+                format!("couldn't parse tag value: {}", value)
+            }
             Error::CouldntParseTimeToNumber(time_string) => format!(
                 "couldn't parse string to number, expected to be string of \
                  seconds: {}",
@@ -65,6 +112,25 @@ impl Error {
             Error::CmusStatusNoData => "CmusStatusBuilder needs CmusData, set \
                                         with `CmusStatusBuilder::data` method"
                 .to_string(),
+            Error::NoServerConfig => "the `tcp` source needs a `server` \
+                                      section in the config"
+                .to_string(),
+            Error::InvalidSource(source) => format!(
+                "invalid source '{}', expected one of: auto, cmus-remote, \
+                 socket, tcp, stdin",
+                source
+            ),
+            Error::I3barStdinSource => "the `i3bar` command reads the status \
+                                        on every update, so it can't read \
+                                        it from stdin, use a file instead"
+                .to_string(),
+            Error::InvalidOutput(output) => format!(
+                "invalid output '{}', expected one of: text, waybar",
+                output
+            ),
+            Error::FailedReadingInput(path, e) => {
+                format!("failed reading input from {:?}\n{}", path, e)
+            }
             Error::NoFormat => {
                 "No output format given for status line".to_string()
             }
@@ -77,6 +143,34 @@ impl Error {
                  long: {}",
                 min_len, config,
             ),
+            Error::ProgressBarConfigInvalid(msg) => {
+                format!("invalid ProgressBar config: {}", msg)
+            }
+            Error::ProgressBarRampEmpty => {
+                "SmoothProgressBar ramp must not be empty".to_string()
+            }
+            Error::InvalidTimeFormat(format) => format!(
+                "invalid time format '{}', expected \"auto\", \"human\" or \
+                 a pattern with %h, %H, %m, %M, %s, %S or %%",
+                format
+            ),
+            Error::InvalidRegex(regex, e) => {
+                format!("invalid regex '{}'\n{}", regex, e)
+            }
+            Error::InvalidExpression(expression, e) => {
+                format!("invalid expression '{}'\n{}", expression, e)
+            }
+            Error::InvalidGlob(glob, e) => {
+                format!("invalid glob pattern '{}'\n{}", glob, e)
+            }
+            Error::InvalidColor(color) => format!(
+                "invalid color '{}', expected a hex color like \"#ff8800\"",
+                color
+            ),
+            Error::InvalidSeconds(name, seconds) => format!(
+                "invalid `{}` {}, expected a positive number of seconds",
+                name, seconds
+            ),
             Error::FailedParsingConfig(Some(filepath), e) => {
                 format!("failed parsing config file at {:?}\n{}", filepath, e)
             }
@@ -93,15 +187,14 @@ impl Error {
             Error::InvalidCommandOption(cmd, opt) => {
                 format!("invalid option '{}' for command '{}'", opt, cmd)
             }
-            Error::CommandTakesNoOptions(cmd) => {
-                format!("command '{}' takes no options", cmd)
+            Error::OptionMissingValue(opt) => {
+                format!("option '{}' requires a value", opt)
             }
         }
     }
 }
 
-impl std::error::Error for Error {
-}
+impl std::error::Error for Error {}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
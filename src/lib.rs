@@ -17,7 +17,8 @@ pub fn run() -> error::MyResult<()> {
     use action::prelude::*;
 
     match action()? {
-        Action::Status => cmus_status::print_cmus_status(),
+        Action::Status(options) => cmus_status::print_cmus_status(options),
+        Action::I3bar(options) => cmus_status::i3bar::run_i3bar(options),
         Action::Help => {
             args::print_help();
             Ok(())
@@ -0,0 +1,162 @@
+//! Tests the native cmus client against fake cmus servers.
+#![cfg(unix)]
+
+extern crate cmus_status_line;
+
+use cmus_status_line::cmus_status::client;
+use cmus_status_line::cmus_status::data::prelude::*;
+use cmus_status_line::config::{Config, ServerConfig};
+use cmus_status_line::error::Error;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+
+/// Starts a fake cmus server on a new UNIX socket,
+/// which answers a single `status` command with the given output.
+fn fake_cmus_server(name: &str, output: &'static str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "cmus-status-line-test-{}-{}.socket",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut command = [0; 7];
+        stream.read_exact(&mut command).unwrap();
+        assert_eq!(&command, b"status\n");
+        stream.write_all(output.as_bytes()).unwrap();
+        stream.write_all(b"\n").unwrap();
+        // Keep the connection open, the client must stop reading
+        // at the answer's terminating empty line.
+        let _ = stream.read(&mut [0; 1]);
+    });
+
+    path
+}
+
+#[test]
+fn socket_status() {
+    let path = fake_cmus_server("status", include_str!("fixtures/playing.txt"));
+    let output = client::get_socket_output_from(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let data = CmusDataParser::default().parse_bytes(&output).unwrap();
+    assert!(data.is_status(&CmusPlaybackStatus::Playing));
+    assert_eq!(data.get_tag("title"), Some("Megalovania".to_string()));
+    assert_eq!(data.get_settings().vol_left, Some(100));
+}
+
+#[test]
+fn socket_missing() {
+    let path = std::env::temp_dir().join("cmus-status-line-test-missing");
+    assert!(client::get_socket_output_from(&path).is_err());
+}
+
+/// Starts a fake cmus server listening on TCP/IP with the given password,
+/// which answers a single `status` command with the given output.
+fn fake_cmus_tcp_server(password: &str, output: &'static str) -> ServerConfig {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let expected_passwd = format!("passwd {}\n", password);
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line != expected_passwd {
+            return;
+        }
+        writer.write_all(b"\n").unwrap();
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        assert_eq!(line, "status\n");
+        writer.write_all(output.as_bytes()).unwrap();
+        writer.write_all(b"\n").unwrap();
+    });
+
+    ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        password: password.to_string(),
+        connect_timeout: 1.0,
+    }
+}
+
+#[test]
+fn tcp_status() {
+    let server =
+        fake_cmus_tcp_server("secret", include_str!("fixtures/playing.txt"));
+    let output = client::get_tcp_output(&server).unwrap();
+
+    let data = CmusDataParser::default().parse_bytes(&output).unwrap();
+    assert!(data.is_status(&CmusPlaybackStatus::Playing));
+    assert_eq!(data.get_tag("title"), Some("Megalovania".to_string()));
+}
+
+#[test]
+fn tcp_wrong_password() {
+    let mut server =
+        fake_cmus_tcp_server("secret", include_str!("fixtures/playing.txt"));
+    server.password = "wrong".to_string();
+
+    match client::get_tcp_output(&server) {
+        Err(Error::CmusAuthenticationFailed(_)) => (),
+        result => panic!("expected authentication failure: {:?}", result),
+    }
+}
+
+#[test]
+fn tcp_invalid_connect_timeout() {
+    let config = |timeout: &str| {
+        Config::from_str(&format!(
+            "format = \"%{{Title}}\"\n\
+             [server]\n\
+             host = \"127.0.0.1\"\n\
+             password = \"secret\"\n\
+             connect_timeout = {}",
+            timeout
+        ))
+    };
+    assert!(config("0.5").is_ok());
+    for timeout in &["0", "-1.0", "nan", "inf", "1e30"] {
+        match config(timeout) {
+            Err(Error::InvalidSeconds(name, _)) => {
+                assert_eq!(name, "server.connect_timeout")
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+            Ok(_) => panic!("accepted connect_timeout {}", timeout),
+        }
+    }
+}
+
+#[test]
+fn config_dir() {
+    use std::env;
+
+    env::set_var("CMUS_HOME", "/tmp/cmus-home");
+    env::set_var("XDG_CONFIG_HOME", "/tmp/xdg-config");
+    assert_eq!(client::get_config_dir(), Some("/tmp/cmus-home".into()));
+
+    // Empty variables are treated as unset, like cmus does.
+    env::set_var("CMUS_HOME", "");
+    assert_eq!(
+        client::get_config_dir(),
+        Some("/tmp/xdg-config/cmus".into())
+    );
+
+    env::remove_var("CMUS_HOME");
+    env::set_var("XDG_CONFIG_HOME", "");
+    env::set_var("HOME", "/tmp/home");
+    assert_eq!(
+        client::get_config_dir(),
+        Some("/tmp/home/.config/cmus".into())
+    );
+}
@@ -0,0 +1,51 @@
+//! Helpers for the tests, which run the binary.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// A fresh directory with the given `config.toml`,
+/// which the binary picks up when it's run in this directory.
+/// The directory is removed when it's dropped.
+pub struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    pub fn new(name: &str, config: &str) -> Self {
+        let path = env::temp_dir().join(format!(
+            "cmus-status-line-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("config.toml"), config).unwrap();
+        Self { path }
+    }
+
+    /// Returns the command running the binary in this directory,
+    /// with piped stdin, stdout and stderr.
+    pub fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_cmus-status-line"));
+        command
+            .args(args)
+            .current_dir(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        command
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+pub fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
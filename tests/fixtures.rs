@@ -0,0 +1,292 @@
+//! Regression tests against real `cmus-remote -Q` outputs,
+//! found in the `tests/fixtures/` directory.
+
+extern crate cmus_status_line;
+
+use cmus_status_line::cmus_status::data::prelude::*;
+use std::convert::TryFrom;
+use std::path::PathBuf;
+
+fn parse(output: &str) -> CmusData {
+    CmusData::try_from(output.to_string()).unwrap()
+}
+
+#[test]
+fn playing() {
+    let data = parse(include_str!("fixtures/playing.txt"));
+    assert!(data.is_status(&CmusPlaybackStatus::Playing));
+    assert_eq!(data.get_title(), Some("100 - Megalovania".to_string()));
+    assert_eq!(data.get_tag("artist"), Some("Toby Fox".to_string()));
+    assert_eq!(
+        data.get_tag("album"),
+        Some("Undertale Soundtrack".to_string())
+    );
+    assert!(!data.is_stream());
+
+    let time = data.get_time().as_ref().unwrap();
+    assert_eq!(time.duration, Some(156));
+    assert_eq!(time.position, 42);
+
+    let settings = data.get_settings();
+    assert_eq!(settings.aaa_mode, Some(CmusAaaMode::All));
+    assert_eq!(settings.shuffle, Some(CmusShuffle::Off));
+    assert_eq!(settings.replaygain, Some(CmusReplayGain::Disabled));
+    assert_eq!(settings.continue_playback, Some(true));
+    assert_eq!(settings.vol_left, Some(100));
+    assert_eq!(settings.vol_right, Some(100));
+    assert_eq!(
+        settings.get_raw("replaygain_preamp"),
+        Some(&"0.000000".to_string())
+    );
+}
+
+#[test]
+fn paused() {
+    let data = parse(include_str!("fixtures/paused.txt"));
+    assert!(data.is_status(&CmusPlaybackStatus::Paused));
+    assert_eq!(data.get_title(), Some("01 some song".to_string()));
+    assert!(!data.has_tag("album"));
+}
+
+#[test]
+fn stopped() {
+    let data = parse(include_str!("fixtures/stopped.txt"));
+    assert!(data.is_status(&CmusPlaybackStatus::Stopped));
+    assert_eq!(data.get_file(), None);
+    assert_eq!(data.get_title(), None);
+    assert!(data.get_time().is_none());
+}
+
+#[test]
+fn stream() {
+    let data = parse(include_str!("fixtures/stream.txt"));
+    assert!(data.is_stream());
+    assert_eq!(
+        data.get_stream(),
+        Some(&"Some Artist - Some Song".to_string())
+    );
+
+    let time = data.get_time().as_ref().unwrap();
+    assert_eq!(time.duration, None);
+    assert_eq!(time.position, 1312);
+}
+
+#[test]
+fn whitespace() {
+    check_whitespace(parse(include_str!("fixtures/whitespace.txt")));
+}
+
+#[test]
+fn whitespace_crlf() {
+    check_whitespace(parse(include_str!("fixtures/whitespace_crlf.txt")));
+}
+
+fn check_whitespace(data: CmusData) {
+    assert_eq!(
+        data.get_file(),
+        Some(&PathBuf::from(
+            "/home/user/Music/Artist  Name/Two  Spaces  Title.flac"
+        ))
+    );
+    assert_eq!(data.get_title(), Some("Two  Spaces  Title".to_string()));
+    assert_eq!(data.get_tag("artist"), Some("Artist  Name".to_string()));
+    assert_eq!(data.get_tag("title"), Some("  Leading spaces".to_string()));
+    assert_eq!(data.get_tag("album"), Some("\tTabbed\tAlbum".to_string()));
+    assert_eq!(data.get_tag("comment"), Some("trailing space ".to_string()));
+}
+
+#[test]
+fn playing_crlf() {
+    let data = parse(include_str!("fixtures/playing_crlf.txt"));
+    assert!(data.is_status(&CmusPlaybackStatus::Playing));
+    assert_eq!(data.get_title(), Some("100 - Megalovania".to_string()));
+    assert_eq!(data.get_tag("artist"), Some("Toby Fox".to_string()));
+    assert_eq!(data.get_time().as_ref().unwrap().position, 42);
+    assert_eq!(data.get_settings().vol_left, Some(100));
+}
+
+#[test]
+fn leading_whitespace() {
+    let data = parse(
+        "  status  playing\n\tfile /music/song.flac\n tag artist Someone\n",
+    );
+    assert!(data.is_status(&CmusPlaybackStatus::Playing));
+    assert_eq!(data.get_file(), Some(&PathBuf::from("/music/song.flac")));
+    assert_eq!(data.get_tag("artist"), Some("Someone".to_string()));
+}
+
+#[test]
+fn cmus_2_10() {
+    let data = parse(include_str!("fixtures/cmus_2_10.txt"));
+    assert_eq!(data.get_tag("tracknumber"), Some("3/12".to_string()));
+
+    let settings = data.get_settings();
+    assert_eq!(settings.aaa_mode, Some(CmusAaaMode::Album));
+    assert_eq!(settings.shuffle, Some(CmusShuffle::Tracks));
+    assert!(settings.is_shuffle());
+    assert_eq!(settings.replaygain, Some(CmusReplayGain::TrackPreferred));
+    assert_eq!(settings.repeat, Some(true));
+    assert_eq!(settings.vol_left, Some(40));
+    assert_eq!(settings.vol_right, Some(60));
+}
+
+#[test]
+fn typed_tags() {
+    let data = parse(include_str!("fixtures/cmus_2_10.txt"));
+    assert_eq!(
+        data.get_track_number(),
+        Some(CmusNumberTag {
+            number: 3,
+            total:  Some(12),
+        })
+    );
+    assert_eq!(
+        data.get_disc_number(),
+        Some(CmusNumberTag {
+            number: 1,
+            total:  Some(2),
+        })
+    );
+    assert_eq!(
+        data.get_date(),
+        Some(CmusDate {
+            year:  2011,
+            month: Some(5),
+            day:   Some(3),
+        })
+    );
+
+    let data = parse(include_str!("fixtures/playing.txt"));
+    assert_eq!(
+        data.get_track_number(),
+        Some(CmusNumberTag {
+            number: 100,
+            total:  None,
+        })
+    );
+    assert_eq!(data.get_disc_number(), None);
+    assert_eq!(data.get_year(), Some(2015));
+    assert_eq!(data.get_date().unwrap().to_string(), "2015");
+    assert_eq!(data.get_tag_number("genre"), None);
+}
+
+#[test]
+fn invalid_typed_tags() {
+    assert!(CmusNumberTag::try_from("A1").is_err());
+    assert!(CmusNumberTag::try_from("3/x").is_err());
+    assert!(CmusDate::try_from("May 2011").is_err());
+    assert!(CmusDate::try_from("2011-13-01").is_err());
+    assert_eq!(
+        CmusDate::try_from("2011-05-03T10:00:00Z")
+            .unwrap()
+            .to_string(),
+        "2011-05-03"
+    );
+}
+
+#[test]
+fn latin1() {
+    let output = include_bytes!("fixtures/latin1.txt");
+    let data = CmusDataParser::default().parse_bytes(output).unwrap();
+    assert_eq!(data.get_title(), Some("Caf\u{FFFD} del Mar".to_string()));
+    assert_eq!(
+        data.get_tag("artist"),
+        Some("Caf\u{FFFD} del Mar".to_string())
+    );
+
+    assert!(CmusDataParser::default()
+        .invalid_utf8(InvalidUtf8Mode::Error)
+        .parse_bytes(output)
+        .is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn latin1_file() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let output = include_bytes!("fixtures/latin1.txt");
+    let data = CmusDataParser::default().parse_bytes(output).unwrap();
+    assert_eq!(
+        data.get_file().unwrap().as_os_str().as_bytes(),
+        &b"/home/user/Music/caf\xe9/Caf\xe9_del_Mar.mp3"[..]
+    );
+
+    let file_line = output.split(|&byte| byte == b'\n').nth(1).unwrap();
+    let output = [&b"status playing\n"[..], file_line].concat();
+    let data = CmusDataParser::default()
+        .invalid_utf8(InvalidUtf8Mode::Error)
+        .parse_bytes(&output)
+        .unwrap();
+    assert_eq!(
+        data.get_file().unwrap().as_os_str().as_bytes(),
+        &b"/home/user/Music/caf\xe9/Caf\xe9_del_Mar.mp3"[..]
+    );
+}
+
+#[test]
+fn unknown_line() {
+    let output =
+        format!("{}lyrics la  la\n", include_str!("fixtures/playing.txt"));
+    assert!(CmusData::try_from(output.clone()).is_err());
+
+    let data = CmusDataParser::default()
+        .mode(ParseMode::Lenient)
+        .parse(output.as_str())
+        .unwrap();
+    assert_eq!(data.get_raw("lyrics"), Some(&"la  la".to_string()));
+}
+
+#[test]
+fn unknown_setting_values() {
+    let mut settings = CmusSettings::default();
+    settings.set("aaa_mode", "genre");
+    settings.set("shuffle", "sometimes");
+    settings.set("replaygain", "loud");
+    settings.set("repeat", "yes");
+    settings.set("vol_left", "300");
+    settings.set("vol_right", "50");
+    assert_eq!(settings.aaa_mode, None);
+    assert_eq!(settings.shuffle, None);
+    assert_eq!(settings.replaygain, None);
+    assert_eq!(settings.repeat, None);
+    assert_eq!(settings.vol_left, None);
+    assert_eq!(settings.vol_right, Some(50));
+    assert_eq!(settings.get_raw("aaa_mode"), Some(&"genre".to_string()));
+    assert_eq!(settings.get_raw("vol_left"), Some(&"300".to_string()));
+    assert!(!settings.is_shuffle());
+
+    let output = include_str!("fixtures/playing.txt")
+        .replace("set aaa_mode all", "set aaa_mode genre")
+        .replace("set shuffle false", "set shuffle sometimes")
+        .replace("set vol_left 100", "set vol_left 300");
+    let data = parse(&output);
+    assert!(data.is_status(&CmusPlaybackStatus::Playing));
+    assert_eq!(data.get_settings().aaa_mode, None);
+    assert_eq!(data.get_settings().vol_right, Some(100));
+}
+
+#[test]
+fn missing_file_path() {
+    for file_line in &["file", "file "] {
+        let output = include_str!("fixtures/playing.txt")
+            .lines()
+            .map(|line| {
+                if line.starts_with("file ") {
+                    file_line
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(CmusData::try_from(output.clone()).is_err());
+
+        let data = CmusDataParser::default()
+            .mode(ParseMode::Lenient)
+            .parse(output.as_str())
+            .unwrap();
+        assert!(data.is_status(&CmusPlaybackStatus::Playing));
+        assert_eq!(data.get_file(), None);
+    }
+}
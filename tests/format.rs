@@ -0,0 +1,721 @@
+//! Rendering `FormatPart`s against `cmus-remote -Q` outputs.
+
+extern crate cmus_status_line;
+
+use cmus_status_line::cmus_status::get_cmus_status_from;
+use cmus_status_line::cmus_status::output::format::prelude::*;
+use cmus_status_line::cmus_status::source::FixtureSource;
+use cmus_status_line::config::Config;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+const PLAYING: &str = include_str!("fixtures/playing.txt");
+const STREAM: &str = include_str!("fixtures/stream.txt");
+
+fn render(format: &str, output: &str) -> String {
+    render_config(&config("", format), output)
+}
+
+fn render_config(config: &str, output: &str) -> String {
+    let config = Config::from_str(config).unwrap();
+    get_cmus_status_from(&mut FixtureSource::new(output), config)
+        .unwrap()
+        .to_string()
+}
+
+/// A config with the given `format`, after the other config `keys`.
+fn config(keys: &str, format: &str) -> String {
+    format!("{}\nformat = '''{}'''", keys, format)
+}
+
+fn is_valid(format: &str) -> bool {
+    Config::from_str(&config("", format)).is_ok()
+}
+
+/// Renders the `FormatExpression` with `If`, to check if it's true.
+fn is_true(expression: &str, output: &str) -> bool {
+    render(&format!("%{{If({}, Text(\"y\"))}}", expression), output) == "y"
+}
+
+#[test]
+fn settings() {
+    let shuffled = PLAYING
+        .replace("set shuffle false", "set shuffle albums")
+        .replace("set aaa_mode all", "set aaa_mode album");
+    assert_eq!(render(r#"%{Setting("aaa_mode")}"#, PLAYING), "all");
+    assert_eq!(
+        render(r#"%{Setting("replaygain_preamp")}"#, PLAYING),
+        "0.000000"
+    );
+    assert_eq!(render(r#"%{Setting("unknown")}"#, PLAYING), "");
+    assert_eq!(render(r#"%{If(IsShuffle, Text("S"))}"#, PLAYING), "");
+    assert_eq!(render(r#"%{If(IsShuffle, Text("S"))}"#, &shuffled), "S");
+    assert_eq!(
+        render(r#"%{If(AaaMode(All), Text("all"))}"#, PLAYING),
+        "all"
+    );
+    assert_eq!(
+        render(r#"%{If(AaaMode(Album), Text("album"))}"#, &shuffled),
+        "album"
+    );
+    assert_eq!(render(r#"%{If(AaaMode(All), Text("all"))}"#, &shuffled), "");
+}
+
+#[test]
+fn raw() {
+    let output = format!("{}lyrics la  la\n", PLAYING);
+    assert_eq!(
+        render_config(
+            &config("parse_mode = \"Lenient\"", r#"[%{Raw("lyrics")}]"#),
+            &output
+        ),
+        "[la  la]"
+    );
+    assert_eq!(
+        render_config(
+            &config("parse_mode = \"Lenient\"", r#"[%{Raw("chords")}]"#),
+            &output
+        ),
+        "[]"
+    );
+}
+
+#[test]
+fn volume() {
+    let volume = |left: u8, right: u8| {
+        PLAYING
+            .replace("set vol_left 100", &format!("set vol_left {}", left))
+            .replace("set vol_right 100", &format!("set vol_right {}", right))
+    };
+    let bar = |percent: u8| {
+        render(
+            r#"%{VolumeBar(Average, "[||||    ]")}"#,
+            &volume(percent, percent),
+        )
+    };
+    let no_volume = PLAYING
+        .replace("set vol_left 100\n", "")
+        .replace("set vol_right 100\n", "");
+    assert_eq!(render("%{Volume(Left)}", &volume(30, 70)), "30");
+    assert_eq!(render("%{Volume(Right)}", &volume(30, 70)), "70");
+    assert_eq!(render("%{Volume(Average)}", &volume(30, 70)), "50");
+    assert_eq!(render("%{Volume(Average)}", &no_volume), "");
+    assert_eq!(render(r#"%{VolumeBar(Left, "[| ]")}"#, &no_volume), "");
+    assert_eq!(bar(0), "[        ]");
+    assert_eq!(bar(50), "[||||    ]");
+    assert_eq!(bar(100), "[||||||||]");
+}
+
+#[test]
+fn track_number_and_year() {
+    let tagged = PLAYING
+        .replace("tag tracknumber 100", "tag tracknumber 3/12")
+        .replace("tag date 2015", "tag date 2015-09-15");
+    let untagged = PLAYING
+        .replace("tag tracknumber 100\n", "")
+        .replace("tag date 2015\n", "");
+    assert_eq!(render("%{TrackNumber()}", &tagged), "3");
+    assert_eq!(render("%{TrackNumber(pad: 2)}", &tagged), "03");
+    assert_eq!(render("%{TrackNumber(pad: 2)}", PLAYING), "100");
+    assert_eq!(render("%{TrackTotal(pad: 3)}", &tagged), "012");
+    assert_eq!(render("%{Year}", &tagged), "2015");
+    assert_eq!(render("%{Year}", PLAYING), "2015");
+    assert_eq!(render("[%{TrackNumber(pad: 2)}]", &untagged), "[]");
+    assert_eq!(render("[%{Year}]", &untagged), "[]");
+}
+
+#[test]
+fn time_formats() {
+    let format =
+        |format: &str, time| TimeFormat::try_from(format).unwrap().format(time);
+    assert_eq!(format("auto", 83), "1:23");
+    assert_eq!(format("auto", 3683), "1:01:23");
+    assert_eq!(format("%m:%S", 3683), "61:23");
+    assert_eq!(format("%H:%M:%S", 83), "00:01:23");
+    assert_eq!(format("%ss", 83), "83s");
+    assert_eq!(format("100%%", 83), "100%");
+    assert_eq!(format("human", 42), "42 sec");
+    assert_eq!(format("human", 185), "3 min");
+    assert_eq!(format("human", 3900), "1 h 5 min");
+    assert!(TimeFormat::try_from("%x").is_err());
+    assert!(TimeFormat::try_from("%").is_err());
+}
+
+#[test]
+fn time_parts() {
+    assert_eq!(
+        render(r#"%{Position("auto")} / %{Duration("auto")}"#, PLAYING),
+        "0:42 / 2:36"
+    );
+    assert_eq!(render(r#"%{Remaining("-%m:%S")}"#, PLAYING), "-1:54");
+    assert_eq!(render(r#"%{Remaining("human")}"#, PLAYING), "1 min left");
+    assert_eq!(
+        render(r#"%{Position("auto")}%{Duration("auto")}"#, STREAM),
+        "21:52"
+    );
+    assert_eq!(render(r#"%{Remaining("auto")}"#, STREAM), "");
+}
+
+#[test]
+fn percent() {
+    assert_eq!(render("%{Percent()}", PLAYING), "27%");
+    assert_eq!(
+        render("%{Percent(decimals: 1, rounding: Floor)}", PLAYING),
+        "26.9%"
+    );
+    assert_eq!(
+        render(
+            r#"%{Percent(decimals: 2, suffix: "", rounding: Ceil)}"#,
+            PLAYING
+        ),
+        "26.93"
+    );
+    let many_decimals = render("%{Percent(decimals: 40)}", PLAYING);
+    assert!(many_decimals.starts_with("26.92"), "{}", many_decimals);
+    assert_eq!(many_decimals, render("%{Percent(decimals: 6)}", PLAYING));
+    assert_eq!(render("%{Percent()}", STREAM), "");
+
+    let zero_duration = "status playing\nfile /a.mp3\nduration 0\nposition 3\n";
+    assert_eq!(render("%{Percent()}", zero_duration), "");
+    assert_eq!(render(r#"%{ProgressBar("<-->")}"#, zero_duration), "<-->");
+}
+
+#[test]
+fn smooth_progress_bar() {
+    assert_eq!(
+        render(r#"%{SmoothProgressBar(bar: "[█         ]")}"#, PLAYING),
+        "[██▊       ]"
+    );
+    assert_eq!(
+        render(r#"%{SmoothProgressBar(bar: "[#  ]", ramp: ".:")}"#, PLAYING),
+        "[:  ]"
+    );
+    assert_eq!(
+        render(r#"%{SmoothProgressBar(bar: "[#   ]")}"#, STREAM),
+        "[    ]"
+    );
+
+    let ramp = ProgressBarRamp::default();
+    let bar = ProgressBarConfig::try_from("#-".to_string()).unwrap();
+    assert_eq!(bar.text_with_percentage(1.0), "##");
+    assert_eq!(bar.text_with_percentage_smooth(1.0, &ramp), "##");
+    assert_eq!(bar.text_with_percentage_smooth(0.0, &ramp), "--");
+    assert_eq!(bar.text_with_percentage_smooth(0.5, &ramp), "#-");
+    assert_eq!(bar.text_with_percentage_smooth(0.99, &ramp), "#█");
+    assert_eq!(bar.text_with_filled(3), "##");
+}
+
+#[test]
+fn structured_progress_bar() {
+    assert_eq!(
+        render(
+            r#"%{ProgressBar((width: 12, start: "[", end: "]", full: "=", head: ">"))}"#,
+            PLAYING
+        ),
+        "[===>      ]"
+    );
+    assert_eq!(
+        render(
+            r#"%{ProgressBar((width: 10, start: "|[", end: "]|", full: "*", empty: "."))}"#,
+            PLAYING
+        ),
+        "|[**....]|"
+    );
+    assert_eq!(render(r#"%{ProgressBar("<##-->")}"#, PLAYING), "<#--->");
+
+    assert!(!is_valid(
+        r#"%{ProgressBar((width: 2, start: "[", end: "]"))}"#
+    ));
+    assert!(!is_valid(r#"%{ProgressBar((width: 10, full: "=="))}"#));
+}
+
+#[test]
+fn time_expressions() {
+    assert!(is_true("HasTime", PLAYING));
+    assert!(is_true("PositionAtLeast(42)", PLAYING));
+    assert!(!is_true("PositionAtLeast(43)", PLAYING));
+    assert!(is_true("RemainingLessThan(115)", PLAYING));
+    assert!(!is_true("RemainingLessThan(114)", PLAYING));
+    assert!(is_true("DurationLongerThan(155)", PLAYING));
+    assert!(!is_true("DurationLongerThan(156)", PLAYING));
+    assert!(is_true("PercentAbove(26.9)", PLAYING));
+    assert!(!is_true("PercentAbove(27.0)", PLAYING));
+
+    assert!(is_true("HasTime", STREAM));
+    assert!(!is_true("RemainingLessThan(100000)", STREAM));
+    assert!(!is_true("DurationLongerThan(0)", STREAM));
+    assert!(!is_true("PercentAbove(0.0)", STREAM));
+    assert!(!is_true("HasTime", include_str!("fixtures/stopped.txt")));
+}
+
+#[test]
+fn tag_and_file_expressions() {
+    assert!(is_true(r#"TagEquals("genre", "Soundtrack")"#, PLAYING));
+    assert!(!is_true(r#"TagEquals("genre", "soundtrack")"#, PLAYING));
+    assert!(!is_true(r#"TagEquals("mood", "")"#, PLAYING));
+    assert!(is_true(r#"TagMatches("album", "^Undertale")"#, PLAYING));
+    assert!(is_true(r#"TagMatches("date", "^[0-9]{4}$")"#, PLAYING));
+    assert!(!is_true(r#"TagMatches("genre", "sound")"#, PLAYING));
+    assert!(is_true(r#"TagMatchesNoCase("genre", "sound")"#, PLAYING));
+    assert!(is_true(r#"TagCompare("date", Gt, 1980)"#, PLAYING));
+    assert!(is_true(r#"TagCompare("date", Eq, 2015)"#, PLAYING));
+    assert!(!is_true(r#"TagCompare("date", Lt, 1980)"#, PLAYING));
+    assert!(is_true(r#"TagCompare("tracknumber", Ge, 100)"#, PLAYING));
+    assert!(!is_true(r#"TagCompare("genre", Ne, 0)"#, PLAYING));
+    assert!(is_true(r#"HasExtension("mp3")"#, PLAYING));
+    assert!(is_true(r#"HasExtension(".MP3")"#, PLAYING));
+    assert!(!is_true(r#"HasExtension("flac")"#, PLAYING));
+    assert!(is_true(r#"FileMatches("**/*.mp3")"#, PLAYING));
+    assert!(!is_true(r#"FileMatches("*.flac")"#, PLAYING));
+
+    assert!(!is_valid(r#"%{If(TagMatches("title", "("), Title)}"#));
+    assert!(!is_valid(r#"%{If(FileMatches("[a"), Title)}"#));
+}
+
+#[test]
+fn all_any_expressions() {
+    assert!(is_true(
+        "All([IsStatus(Playing), HasTag(\"artist\"), True])",
+        PLAYING
+    ));
+    assert!(!is_true("All([IsStatus(Playing), IsStream])", PLAYING));
+    assert!(is_true("All([])", PLAYING));
+    assert!(is_true("Any([IsStream, IsStatus(Playing)])", PLAYING));
+    assert!(!is_true("Any([IsStream, False])", PLAYING));
+    assert!(!is_true("Any([])", PLAYING));
+}
+
+#[test]
+fn text_expressions() {
+    assert!(is_true(
+        &format!("{:?}", "status == playing && has(artist)"),
+        PLAYING
+    ));
+    assert!(is_true(
+        &format!("{:?}", "status == paused || status == playing"),
+        PLAYING
+    ));
+    assert!(!is_true(&format!("{:?}", "status != playing"), PLAYING));
+    assert!(is_true(
+        &format!("{:?}", "!(stream || status == stopped)"),
+        PLAYING
+    ));
+    assert!(is_true(
+        &format!("{:?}", "tag(genre) == Soundtrack && tag(date) < 2020"),
+        PLAYING
+    ));
+    assert!(is_true(
+        &format!("{:?}", r#"tag(album) =~ "(?i)^undertale" && ext(mp3)"#),
+        PLAYING
+    ));
+    assert!(is_true(
+        &format!("{:?}", r#"tag(artist) != "Someone Else""#),
+        PLAYING
+    ));
+    assert!(is_true(
+        &format!("{:?}", r#"file("**/*.mp3") && position >= 42"#),
+        PLAYING
+    ));
+    assert!(is_true(
+        &format!(
+            "{:?}",
+            "remaining < 200 && duration > 100 && percent > 20.5"
+        ),
+        PLAYING
+    ));
+    assert!(is_true(
+        &format!("{:?}", "aaa_mode == all && !shuffle && continue"),
+        PLAYING
+    ));
+    assert!(is_true(&format!("{:?}", "true && !false"), PLAYING));
+    assert_eq!(
+        render(r#"%{IfElse("stream", Stream, Title)}"#, PLAYING),
+        "100 - Megalovania"
+    );
+    assert_eq!(
+        render(r#"%{If(Not(Expr("stream")), Text("y"))}"#, PLAYING),
+        "y"
+    );
+
+    let error = |expression: &str| {
+        FormatExpression::try_from(expression)
+            .unwrap_err()
+            .to_string()
+    };
+    assert!(error("status == plying").contains("unknown status `plying`"));
+    assert!(error("has(artist) &&").contains("found end of expression"));
+    assert!(error("(stream").contains("expected `)`"));
+    assert!(error("position > 3").contains("expected `>=`"));
+    assert!(error("tag(x) =~ \"(\"").contains("invalid regex"));
+    assert!(error("foo").contains("unknown expression `foo`"));
+    assert!(Config::from_str(
+        "format = '''%{If(\"status = playing\", Title)}'''"
+    )
+    .is_err());
+}
+
+#[test]
+fn truncate() {
+    use cmus_status_line::cmus_status::output::text;
+
+    let output = "status playing\nfile /a.mp3\ntag title 東京タワーの歌\n\
+                  tag artist Cafe\u{301} Tacvba\n";
+    assert_eq!(
+        render(r#"%{Truncate(Tag("title"), 9)}"#, output),
+        "東京タ..."
+    );
+    assert_eq!(
+        render(r#"%{Truncate(Tag("artist"), 20)}"#, output),
+        "Cafe\u{301} Tacvba"
+    );
+    assert_eq!(
+        render(
+            r#"%{TruncateWith(part: Tag("artist"), width: 5, ellipsis: "…")}"#,
+            output
+        ),
+        "Cafe\u{301}…"
+    );
+    assert_eq!(
+        render(
+            r#"%{TruncateWith(part: Tag("title"), width: 7, side: Start)}"#,
+            output
+        ),
+        "...の歌"
+    );
+    assert_eq!(
+        render(
+            r#"%{TruncateWith(part: Tag("artist"), width: 8, ellipsis: "…", side: Middle)}"#,
+            output
+        ),
+        "Cafe\u{301}…vba"
+    );
+    assert_eq!(render(r#"%{Truncate(Tag("title"), 3)}"#, output), "東");
+
+    assert_eq!(text::display_width("東京"), 4);
+    assert_eq!(text::take_start("東京", 3), "東");
+    assert_eq!(text::take_end("Cafe\u{301}", 1), "e\u{301}");
+}
+
+#[test]
+fn pad_and_fixed_width() {
+    let output = "status playing\nfile /a.mp3\ntag title 東京タワー\n\
+                  tag artist Abc\n";
+    assert_eq!(
+        render(r#"[%{Pad(Tag("artist"), 6, Left, '.')}]"#, output),
+        "[Abc...]"
+    );
+    assert_eq!(
+        render(r#"[%{Pad(Tag("artist"), 6, Right, ' ')}]"#, output),
+        "[   Abc]"
+    );
+    assert_eq!(
+        render(r#"[%{Pad(Tag("artist"), 5, Right, '"')}]"#, output),
+        r#"[""Abc]"#
+    );
+    assert_eq!(
+        render(r#"[%{Pad(Tag("artist"), 5, Left, '}')}]"#, output),
+        "[Abc}}]"
+    );
+    assert_eq!(
+        render(r#"[%{Pad(Tag("artist"), 5, Left, '\'')}]"#, output),
+        "[Abc'']"
+    );
+    assert_eq!(
+        render(r#"[%{Pad(Tag("artist"), 6, Center, '-')}]"#, output),
+        "[-Abc--]"
+    );
+    assert_eq!(
+        render(r#"[%{Pad(Tag("title"), 11, Right, ' ')}]"#, output),
+        "[ 東京タワー]"
+    );
+    assert_eq!(
+        render(r#"[%{Pad(Tag("artist"), 2, Left, ' ')}]"#, output),
+        "[Abc]"
+    );
+    assert_eq!(
+        render(r#"[%{Pad(Tag("album"), 2, Left, ' ')}]"#, output),
+        "[]"
+    );
+
+    assert_eq!(
+        render(r#"[%{FixedWidth(part: Tag("artist"), width: 5)}]"#, output),
+        "[Abc  ]"
+    );
+    assert_eq!(
+        render(
+            r#"[%{FixedWidth(part: Tag("title"), width: 8, ellipsis: "…")}]"#,
+            output
+        ),
+        "[東京タ… ]"
+    );
+    assert_eq!(
+        render(
+            r#"[%{FixedWidth(part: Tag("album"), width: 3, fill: '-')}]"#,
+            output
+        ),
+        "[---]"
+    );
+    assert_eq!(
+        render(
+            r#"[%{FixedWidth(part: Tag("artist"), width: 5, align: Right, fill: '東')}]"#,
+            output
+        ),
+        "[東Abc]"
+    );
+}
+
+#[test]
+fn text_transformations() {
+    let output = "status playing\nfile /a.mp3\n\
+                  tag title  don't STOP (Remastered 2011) \n\
+                  tag artist Abc feat. Def\n";
+    assert_eq!(
+        render(r#"%{Upper(Tag("artist"))}"#, output),
+        "ABC FEAT. DEF"
+    );
+    assert_eq!(
+        render(r#"%{Lower(Tag("artist"))}"#, output),
+        "abc feat. def"
+    );
+    assert_eq!(
+        render(r#"[%{Trim(Tag("title"))}]"#, output),
+        "[don't STOP (Remastered 2011)]"
+    );
+    assert_eq!(
+        render(r#"[%{TitleCase(Trim(Tag("title")))}]"#, output),
+        "[Don't Stop (Remastered 2011)]"
+    );
+    assert_eq!(
+        render(
+            r#"[%{Replace(Trim(Tag("title")), " \\(Remastered \\d+\\)$", "")}]"#,
+            output
+        ),
+        "[don't STOP]"
+    );
+    assert_eq!(
+        render(
+            r#"%{Replace(Tag("artist"), "(.+) feat\\. (?P<guest>.+)", "$1 ft. ${guest}")}"#,
+            output
+        ),
+        "Abc ft. Def"
+    );
+    assert_eq!(render(r#"[%{Upper(Tag("album"))}]"#, output), "[]");
+    assert!(
+        Config::from_str(r#"format = '''%{Replace(Title, "(", "")}'''"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn escape_targets() {
+    let escape = |target: EscapeTarget, text: &str| target.escape(text);
+    assert_eq!(
+        escape(EscapeTarget::Html, "<a & 'b'>"),
+        "&lt;a &amp; &#x27;b&#x27;&gt;"
+    );
+    assert_eq!(
+        escape(EscapeTarget::Pango, "<a & \"b\">"),
+        "&lt;a &amp; &quot;b&quot;&gt;"
+    );
+    assert_eq!(
+        escape(EscapeTarget::Json, "a \"b\"\\\n\u{1}"),
+        "a \\\"b\\\"\\\\\\n\\u0001"
+    );
+    assert_eq!(escape(EscapeTarget::Shell, "a.mp3"), "a.mp3");
+    assert_eq!(escape(EscapeTarget::Shell, ""), "''");
+    assert_eq!(escape(EscapeTarget::Shell, "don't $x"), "'don'\\''t $x'");
+    assert_eq!(escape(EscapeTarget::Tmux, "#1 #[fg=red]"), "##1 ##[fg=red]");
+    assert_eq!(escape(EscapeTarget::Lemonbar, "100%{F-}"), "100%%{F-}");
+    assert_eq!(escape(EscapeTarget::Polybar, "50%"), "50%%");
+}
+
+#[test]
+fn escape_parts() {
+    let output = "status playing\nfile /a.mp3\ntag title #1 <Hits> & 50%\n";
+    assert_eq!(
+        render(r#"%{Escape(Tag("title"), Tmux)}"#, output),
+        "##1 <Hits> & 50%"
+    );
+    assert_eq!(
+        render(r#"%{Escape(Tag("title"), Pango)}"#, output),
+        "#1 &lt;Hits&gt; &amp; 50%"
+    );
+
+    // Only text derived from cmus' data is escaped.
+    assert_eq!(
+        render_config(
+            &config("escape = \"Pango\"", r#"<b>%{Tag("title")}</b>"#),
+            output
+        ),
+        "<b>#1 &lt;Hits&gt; &amp; 50%</b>"
+    );
+    assert_eq!(
+        render_config(
+            &config(
+                "escape = \"Pango\"",
+                r#"%{Container([Text("<i>"), Tag("title"), Text("</i>")])}"#
+            ),
+            output
+        ),
+        "<i>#1 &lt;Hits&gt; &amp; 50%</i>"
+    );
+    // Text transformations see the unescaped text.
+    assert_eq!(
+        render_config(
+            &config("escape = \"Pango\"", r#"%{Truncate(Tag("title"), 8)}"#),
+            output
+        ),
+        "#1 &lt;H..."
+    );
+    // Text from the config stays unescaped inside text transformations.
+    let pango = |format: &str| {
+        render_config(&config("escape = \"Pango\"", format), output)
+    };
+    assert_eq!(
+        pango(
+            r#"%{Truncate(Container([Text("<b>"), Tag("title"), Text("</b>")]), 40)}"#
+        ),
+        "<b>#1 &lt;Hits&gt; &amp; 50%</b>"
+    );
+    assert_eq!(
+        pango(r#"%{Upper(Container([Text("<i>"), Tag("title")]))}"#),
+        "<I>#1 &lt;HITS&gt; &amp; 50%"
+    );
+    assert_eq!(
+        pango(
+            r#"%{Replace(Container([Tag("title"), Text("<br>")]), "<", "[")}"#
+        ),
+        "#1 [Hits&gt; &amp; 50%[br>"
+    );
+    assert_eq!(
+        pango(
+            r#"%{TruncateWith(part: Tag("title"), width: 6, ellipsis: "<>")}"#
+        ),
+        "#1 &lt;<>"
+    );
+    // Escaped parts aren't escaped again.
+    assert_eq!(
+        render_config(
+            &config("escape = \"Pango\"", r#"%{HtmlEscape(Tag("title"))}"#),
+            output
+        ),
+        "#1 &lt;Hits&gt; &amp; 50%"
+    );
+    assert_eq!(
+        render_config(
+            &config(
+                "escape = \"Lemonbar\"",
+                r#"%{Escape(Tag("title"), Tmux)}"#
+            ),
+            output
+        ),
+        "##1 <Hits> & 50%"
+    );
+    assert_eq!(
+        render_config(
+            &config("escape = \"Polybar\"", r#"%{Tag("title")} 100%%"#),
+            output
+        ),
+        "#1 <Hits> & 50%% 100%%"
+    );
+}
+
+#[test]
+fn styles() {
+    let output = "status playing\nfile /a.mp3\ntag title Song\n";
+    let style = |target: &str, style: &str| {
+        let format = format!("%{{Style(Tag(\"title\"), {})}}", style);
+        let keys = format!("style_target = \"{}\"", target);
+        render_config(
+            &format!(
+                "{}\n[theme]\naccent = \"#ff8800\"",
+                config(&keys, &format)
+            ),
+            output,
+        )
+    };
+    assert_eq!(
+        style("Ansi", r#"(fg: "red", bg: "bright-blue", bold: true)"#),
+        "\x1b[1;31;104mSong\x1b[0m"
+    );
+    assert_eq!(
+        style("Ansi", r#"(fg: "accent", underline: true)"#),
+        "\x1b[4;38;2;255;136;0mSong\x1b[0m"
+    );
+    assert_eq!(style("Ansi", "()"), "Song");
+    assert_eq!(
+        style("Tmux", r#"(fg: "brightred", bg: "accent", italic: true)"#),
+        "#[fg=brightred,bg=#ff8800,italics]Song#[default]"
+    );
+    assert_eq!(
+        style("Pango", r#"(fg: "accent", bold: true)"#),
+        r##"<span foreground="#ff8800" weight="bold">Song</span>"##
+    );
+    assert_eq!(
+        style("Lemonbar", r#"(fg: "red", bg: "accent", underline: true)"#),
+        "%{F#cd0000}%{B#ff8800}%{+u}Song%{-u}%{B-}%{F-}"
+    );
+    assert_eq!(
+        style("Polybar", r##"(fg: "#00FF00", bold: true)"##),
+        "%{F#00ff00}Song%{F-}"
+    );
+    assert_eq!(
+        style("Conky", r#"(fg: "accent", bg: "red")"#),
+        "${color #ff8800}Song${color}"
+    );
+    // Unknown theme colors are ignored.
+    assert_eq!(style("Conky", r#"(fg: "unknown")"#), "Song");
+
+    // Without a `style_target`, the text isn't styled.
+    assert_eq!(
+        render(r#"%{Style(Tag("title"), (fg: "red"))}"#, output),
+        "Song"
+    );
+    // Styles wrap the escaped text.
+    assert_eq!(
+        render_config(
+            "escape = \"Tmux\"\nstyle_target = \"Tmux\"\nformat = \
+             '''%{Style(Tag(\"title\"), (bold: true))}'''",
+            "status playing\nfile /a.mp3\ntag title #1\n"
+        ),
+        "#[bold]##1#[default]"
+    );
+    // Text transformations don't see the style markup.
+    let pango = |format: &str| {
+        render_config(
+            &config("escape = \"Pango\"\nstyle_target = \"Pango\"", format),
+            "status playing\nfile /a.mp3\ntag title A&B\n",
+        )
+    };
+    assert_eq!(
+        pango(r#"%{Pad(Style(Tag("title"), (bold: true)), 5, Left, ' ')}"#),
+        r#"<span weight="bold">A&amp;B</span>  "#
+    );
+    assert_eq!(
+        pango(r#"%{Pad(Style(Tag("title"), (bold: true)), 5, Right, '.')}"#),
+        r#"..<span weight="bold">A&amp;B</span>"#
+    );
+    assert_eq!(
+        pango(r#"%{Truncate(Style(Tag("title"), (italic: true)), 2)}"#),
+        r#"<span style="italic">A&amp;</span>"#
+    );
+    assert_eq!(
+        pango(
+            r#"%{Truncate(Container([Text("x "), Style(Tag("title"), (bold: true))]), 6)}"#
+        ),
+        r#"x <span weight="bold">A&amp;B</span>"#
+    );
+    assert_eq!(
+        pango(r#"%{Upper(Style(Tag("title"), (fg: "red")))}"#),
+        r##"<span foreground="#cd0000">A&amp;B</span>"##
+    );
+    assert_eq!(
+        pango(r#"%{Lower(Style(Tag("title"), (fg: "red")))}"#),
+        r##"<span foreground="#cd0000">a&amp;b</span>"##
+    );
+    assert_eq!(
+        pango(r#"%{Escape(Style(Tag("title"), (bold: true)), Pango)}"#),
+        r#"<span weight="bold">A&amp;B</span>"#
+    );
+    assert!(!is_valid(r##"%{Style(Title, (fg: "#ff88"))}"##));
+}
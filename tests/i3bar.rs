@@ -0,0 +1,112 @@
+//! The `i3bar` command, reading the first updates and killing it.
+
+mod common;
+
+use common::{fixture, TestDir};
+use std::io::{BufRead, BufReader};
+
+const CONFIG: &str = r##"
+format = "%{Title}"
+style_target = "Pango"
+
+[theme]
+accent = "#ff8800"
+
+[i3bar]
+interval = 0.1
+
+[[i3bar.blocks]]
+name = "cmus-title"
+format = "%{Style(Title, (bold: true))}"
+short_format = "%{Truncate(Title, 8)}"
+color = "accent"
+
+[[i3bar.blocks]]
+name = "cmus-empty"
+format = "%{Tag(\"mood\")}"
+
+[[i3bar.blocks]]
+format = "%{Percent()} \"%{Status}\""
+"##;
+
+/// Runs the `i3bar` command with the given config, reading the `input`
+/// fixture, and returns its first lines.
+fn run(name: &str, config: &str, input: &str, lines: usize) -> Vec<String> {
+    let dir = TestDir::new(&format!("i3bar-{}", name), config);
+    let input = fixture(input);
+    let mut child = dir
+        .command(&["i3bar", "--input", input.to_str().unwrap()])
+        .spawn()
+        .unwrap();
+    let output = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .take(lines)
+        .map(Result::unwrap)
+        .collect();
+    child.kill().unwrap();
+    child.wait().unwrap();
+    output
+}
+
+#[test]
+fn blocks() {
+    let block = r##"[{"name":"cmus-title","full_text":"<span weight=\"bold\">100 - Megalovania</span>","short_text":"100 -...","color":"#ff8800","markup":"pango"},{"name":"cmus","full_text":"27% \"Playing\"","markup":"pango"}],"##;
+    assert_eq!(
+        run("blocks", CONFIG, "playing.txt", 4),
+        vec![r#"{"version":1}"#, "[", block, block]
+    );
+}
+
+#[test]
+fn default_block() {
+    let lines = run("default", r#"format = "%{Status}""#, "playing.txt", 3);
+    assert_eq!(
+        lines,
+        vec![
+            r#"{"version":1}"#,
+            "[",
+            r#"[{"name":"cmus","full_text":"Playing"}],"#
+        ]
+    );
+}
+
+#[test]
+fn pango_escapes_data() {
+    let config = r#"
+format = "%{Title} <b>%{Status}</b>"
+style_target = "Pango"
+"#;
+    assert_eq!(
+        run("pango", config, "markup.txt", 3)[2],
+        r#"[{"name":"cmus","full_text":"AC&amp;DC &lt;Live&gt; <b>Playing</b>","markup":"pango"}],"#
+    );
+}
+
+#[test]
+fn stdin_source() {
+    let dir = TestDir::new("i3bar-stdin", r#"format = "%{Status}""#);
+    let output = dir.command(&["i3bar", "--input", "-"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("stdin"));
+}
+
+#[test]
+fn invalid_interval() {
+    for interval in &["0", "-1.0", "nan", "inf"] {
+        let config = format!(
+            "format = \"%{{Status}}\"\n[i3bar]\ninterval = {}",
+            interval
+        );
+        let dir = TestDir::new("i3bar-interval", &config);
+        let fixture = fixture("playing.txt");
+        let output = dir
+            .command(&["i3bar", "--input", fixture.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(!output.status.success(), "accepted interval {}", interval);
+        assert!(
+            String::from_utf8_lossy(&output.stderr).contains("i3bar.interval")
+        );
+    }
+}
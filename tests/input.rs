@@ -0,0 +1,70 @@
+//! Golden-output tests, rendering fixtures with `status --input`.
+
+mod common;
+
+use common::{fixture, TestDir};
+use std::fs;
+use std::io::Write;
+use std::process::Output;
+
+const FORMAT: &str = r#"format = "%{Title} - %{Status}""#;
+
+/// Runs the binary with the `FORMAT` config, writing `stdin` to it.
+fn run(name: &str, args: &[&str], stdin: Option<&[u8]>) -> Output {
+    let dir = TestDir::new(name, FORMAT);
+    let mut child = dir.command(args).spawn().unwrap();
+    {
+        let mut child_stdin = child.stdin.take().unwrap();
+        if let Some(input) = stdin {
+            child_stdin.write_all(input).unwrap();
+        }
+    }
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn input_file() {
+    let path = fixture("playing.txt");
+    let output =
+        run("file", &["status", "--input", path.to_str().unwrap()], None);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"100 - Megalovania - Playing\n");
+}
+
+#[test]
+fn input_stdin() {
+    let input = fs::read(fixture("paused.txt")).unwrap();
+    let output = run("stdin", &["-i", "-"], Some(&input));
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"01 some song - Paused\n");
+}
+
+#[test]
+fn input_missing_file() {
+    let output = run("missing", &["--input", "/nonexistent/cmus.txt"], None);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn output_waybar() {
+    let path = fixture("playing.txt");
+    let output = run(
+        "waybar",
+        &["--input", path.to_str().unwrap(), "--output", "waybar"],
+        None,
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "{\"text\":\"100 - Megalovania - Playing\",\"class\":\"playing\",\
+         \"percentage\":27}\n"
+    );
+}
+
+#[test]
+fn output_invalid() {
+    let output = run("output-invalid", &["-o", "xml"], None);
+    assert!(!output.status.success());
+    let output = run("output-i3bar", &["i3bar", "-o", "waybar"], None);
+    assert!(!output.status.success());
+}
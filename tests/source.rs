@@ -0,0 +1,71 @@
+//! Feeding `StatusOutput` from custom `CmusSource`s.
+
+extern crate cmus_status_line;
+
+use cmus_status_line::cmus_status::get_cmus_status_from;
+use cmus_status_line::cmus_status::source::prelude::*;
+use cmus_status_line::config::Config;
+use cmus_status_line::error::prelude::*;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+const PLAYING: &str = include_str!("fixtures/playing.txt");
+
+fn config(toml: &str) -> Config {
+    Config::from_str(toml).unwrap()
+}
+
+#[test]
+fn fixture_source() {
+    let mut source = FixtureSource::new(PLAYING);
+    let status = get_cmus_status_from(
+        &mut source,
+        config(r#"format = "%{Title} [%{Status}]""#),
+    )
+    .unwrap();
+    assert_eq!(status.to_string(), "100 - Megalovania [Playing]");
+}
+
+struct CountingSource(usize);
+
+impl CmusSource for CountingSource {
+    fn get_output(&mut self) -> MyResult<Vec<u8>> {
+        self.0 += 1;
+        Ok(PLAYING.as_bytes().to_vec())
+    }
+}
+
+#[test]
+fn custom_source() {
+    let mut source = CountingSource(0);
+    let status =
+        get_cmus_status_from(&mut source, config(r#"format = "%{Title}""#))
+            .unwrap();
+    assert_eq!(status.to_string(), "100 - Megalovania");
+    assert_eq!(source.0, 1);
+}
+
+#[test]
+fn file_source() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/paused.txt");
+    let mut source = FileSource::new(&path);
+    assert_eq!(source.get_output().unwrap(), std::fs::read(path).unwrap());
+
+    assert!(FileSource::new("/nonexistent/cmus.txt")
+        .get_output()
+        .is_err());
+}
+
+#[test]
+fn source_config() {
+    assert_eq!(config("format = \"\"").source, SourceConfig::Auto);
+    assert_eq!(
+        config("format = \"\"\nsource = \"Stdin\"").source,
+        SourceConfig::Stdin
+    );
+    assert_eq!(
+        config("format = \"\"\nsource = { File = \"/tmp/cmus.txt\" }").source,
+        SourceConfig::File(PathBuf::from("/tmp/cmus.txt"))
+    );
+}
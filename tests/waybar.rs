@@ -0,0 +1,57 @@
+//! Waybar JSON output, rendered against `cmus-remote -Q` outputs.
+
+extern crate cmus_status_line;
+
+use cmus_status_line::cmus_status::source::FixtureSource;
+use cmus_status_line::cmus_status::waybar::get_waybar_json_from;
+use cmus_status_line::config::Config;
+use std::str::FromStr;
+
+const PLAYING: &str = include_str!("fixtures/playing.txt");
+const PAUSED: &str = include_str!("fixtures/paused.txt");
+const STREAM: &str = include_str!("fixtures/stream.txt");
+const STOPPED: &str = include_str!("fixtures/stopped.txt");
+
+fn render(config: &str, output: &str) -> String {
+    let config = Config::from_str(config).unwrap();
+    get_waybar_json_from(&mut FixtureSource::new(output), &config).unwrap()
+}
+
+#[test]
+fn text_class_and_percentage() {
+    let config = r#"format = "%{Status}""#;
+    assert_eq!(
+        render(config, PLAYING),
+        r#"{"text":"Playing","class":"playing","percentage":27}"#
+    );
+    assert_eq!(
+        render(config, PAUSED),
+        r#"{"text":"Paused","class":"paused","percentage":100}"#
+    );
+    assert_eq!(
+        render(config, STREAM),
+        r#"{"text":"Playing","class":"playing"}"#
+    );
+    assert_eq!(
+        render(config, STOPPED),
+        r#"{"text":"Stopped","class":"stopped"}"#
+    );
+}
+
+#[test]
+fn tooltip_and_alt() {
+    let config = r#"
+escape = "Pango"
+format = "%{Tag(\"title\")}"
+
+[waybar]
+tooltip = "%{Tag(\"artist\")} - \"%{Tag(\"title\")}\""
+alt = "%{Status}"
+"#;
+    let output = "status paused\nfile /a.mp3\ntag title <Intro> & Outro\n\
+                  tag artist Someone\n";
+    assert_eq!(
+        render(config, output),
+        r#"{"text":"&lt;Intro&gt; &amp; Outro","alt":"Paused","tooltip":"Someone - \"&lt;Intro&gt; &amp; Outro\"","class":"paused"}"#
+    );
+}